@@ -0,0 +1,60 @@
+use std::fmt;
+
+/// The length a snake has to reach to unlock `Achievement::LongSnake`.
+pub const LONG_SNAKE_LENGTH: usize = 20;
+
+/// A goal reached by the players, unlocked at most once since the game was launched.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Achievement {
+    FirstApple,
+    FirstPowerUp,
+    LongSnake,
+    LevelCompleted,
+}
+
+impl fmt::Display for Achievement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Achievement::FirstApple => write!(f, "First apple"),
+            Achievement::FirstPowerUp => write!(f, "First power-up"),
+            Achievement::LongSnake => write!(f, "Snake of {} segments", LONG_SNAKE_LENGTH),
+            Achievement::LevelCompleted => write!(f, "Level completed"),
+        }
+    }
+}
+
+/// The achievements unlocked by the `AchievementsSystem` from the `GameEvent`s, in the order
+/// they were unlocked.
+#[derive(Clone, Debug, Default)]
+pub struct Achievements {
+    pub unlocked: Vec<Achievement>,
+}
+
+impl Achievements {
+    /// Unlocks `achievement`, returning whether it was not unlocked yet.
+    pub fn unlock(&mut self, achievement: Achievement) -> bool {
+        if self.unlocked.contains(&achievement) {
+            return false;
+        }
+        self.unlocked.push(achievement);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn achievements_are_unlocked_once() {
+        let mut achievements = Achievements::default();
+
+        assert!(achievements.unlock(Achievement::FirstApple));
+        assert!(achievements.unlock(Achievement::LongSnake));
+        assert!(!achievements.unlock(Achievement::FirstApple));
+        assert_eq!(
+            achievements.unlocked,
+            vec![Achievement::FirstApple, Achievement::LongSnake]
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+pub const HEAD_MOUTH_OPEN: &str = "head_mouth_open";
+pub const BODY_GULP: &str = "body_gulp";
+pub const APPLE_IDLE: &str = "apple_idle";
+pub const APPLE_EXPIRING: &str = "apple_expiring";
+pub const DEATH_FLASH: &str = "death_flash";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AnimationFrame {
+    #[serde(default)]
+    pub sprite: Option<String>,
+    #[serde(default = "default_scale")]
+    pub scale: (f32, f32),
+    #[serde(default)]
+    pub tint: Option<[f32; 4]>,
+    pub duration: f32,
+}
+
+fn default_scale() -> (f32, f32) {
+    (1.0, 1.0)
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AnimationClip {
+    pub frames: Vec<AnimationFrame>,
+    #[serde(default)]
+    pub looping: bool,
+}
+
+impl AnimationClip {
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+
+    pub fn frame_at(&self, elapsed: f32) -> Option<&AnimationFrame> {
+        let duration = self.duration();
+        if duration <= 0.0 {
+            return None;
+        }
+
+        let mut time = if self.looping {
+            elapsed % duration
+        } else if elapsed < duration {
+            elapsed
+        } else {
+            return None;
+        };
+
+        for frame in &self.frames {
+            if time < frame.duration {
+                return Some(frame);
+            }
+            time -= frame.duration;
+        }
+        self.frames.last()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SpriteMetadata {
+    pub animations: HashMap<String, AnimationClip>,
+}
+
+impl SpriteMetadata {
+    pub fn sprite_names(&self) -> impl Iterator<Item = &str> {
+        self.animations
+            .values()
+            .flat_map(|clip| clip.frames.iter())
+            .filter_map(|frame| frame.sprite.as_deref())
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    audio::{
+        output::{init_output, Output},
+        Source, SourceHandle, WavFormat,
+    },
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{Settings, Volume};
+
+pub const EAT: &str = "eat";
+pub const TURN: &str = "turn";
+pub const DEATH: &str = "death";
+pub const LEVEL_UP: &str = "level_up";
+pub const MENU: &str = "menu";
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum MusicMode {
+    Game,
+    GameOver,
+    Victory,
+    Menu,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AudioConfig {
+    pub effects: HashMap<String, String>,
+    pub music: HashMap<MusicMode, Vec<String>>,
+}
+
+pub struct Sounds {
+    effects: HashMap<String, SourceHandle>,
+}
+
+impl Sounds {
+    pub fn play(
+        &self,
+        name: &str,
+        storage: &AssetStorage<Source>,
+        output: Option<&Output>,
+        volume: &Volume,
+    ) {
+        if let (Some(output), Some(sound)) = (
+            output,
+            self.effects
+                .get(name)
+                .and_then(|handle| storage.get(handle)),
+        ) {
+            output.play_once(sound, volume.master * volume.effects);
+        }
+    }
+}
+
+pub struct Music {
+    pub mode: MusicMode,
+    tracks: HashMap<MusicMode, Vec<SourceHandle>>,
+    next: usize,
+}
+
+impl Music {
+    pub fn next_track(&mut self) -> Option<SourceHandle> {
+        let tracks = self.tracks.get(&self.mode)?;
+        if tracks.is_empty() {
+            return None;
+        }
+        let track = tracks[self.next % tracks.len()].clone();
+        self.next += 1;
+        Some(track)
+    }
+
+    pub fn set_mode(&mut self, mode: MusicMode) {
+        if self.mode != mode {
+            self.mode = mode;
+            self.next = 0;
+        }
+    }
+}
+
+/// Opens the default audio device and loads the sounds listed in the `AudioConfig`. The sounds
+/// are loaded even without an audio device, every place that plays them skips playback when
+/// there is no `Output`.
+pub fn init_audio(world: &mut World) {
+    init_output(world);
+
+    let (effects, tracks) = {
+        let config = world.read_resource::<AudioConfig>();
+        let loader = world.read_resource::<Loader>();
+        let storage = world.read_resource::<AssetStorage<Source>>();
+        let load = |path: &String| loader.load(path.as_str(), WavFormat, (), &storage);
+
+        let effects = config
+            .effects
+            .iter()
+            .map(|(name, path)| (name.clone(), load(path)))
+            .collect();
+        let tracks = config
+            .music
+            .iter()
+            .map(|(mode, paths)| (*mode, paths.iter().map(load).collect()))
+            .collect();
+        (effects, tracks)
+    };
+
+    world.insert(Sounds { effects });
+    world.insert(Music {
+        mode: MusicMode::Game,
+        tracks,
+        next: 0,
+    });
+}
+
+pub fn play_effect(world: &World, name: &str) {
+    let output = world.try_fetch::<Output>();
+    world.read_resource::<Sounds>().play(
+        name,
+        &world.read_resource::<AssetStorage<Source>>(),
+        output.as_deref(),
+        &world.read_resource::<Settings>().volume,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use amethyst::audio::{output::default_output, AudioSink};
+
+    use super::*;
+
+    #[test]
+    fn output_and_sink_exist_when_a_device_is_available() {
+        let mut world = World::new();
+        init_output(&mut world);
+        let has_device = default_output().is_some();
+        assert_eq!(world.has_value::<Output>(), has_device);
+        assert_eq!(world.has_value::<AudioSink>(), has_device);
+    }
+}
@@ -0,0 +1,229 @@
+use amethyst::{
+    config::Config,
+    ecs::Entity,
+    input::{
+        is_close_requested, Bindings, Button, InputEvent, InputHandler, StringBindings,
+        VirtualKeyCode,
+    },
+    prelude::*,
+};
+use log::warn;
+
+use crate::audio::{play_effect, Music, MusicMode, MENU};
+use crate::menu::{MenuText, COMPACT_ROW_HEIGHT, FIRST_ROW};
+use crate::settings::{save_user_file, UserFiles};
+use crate::snake::{GameState, SnakeGame};
+
+/// Lists every input action with its bindings. Enter waits for the next key or button press and
+/// binds it to the selected action, unless another action already uses it. The bindings are
+/// written to the user bindings file when leaving with Escape.
+#[derive(Default)]
+pub struct BindingsMenuState {
+    actions: Vec<String>,
+    selected: usize,
+    capturing: bool,
+    changed: bool,
+    rows: Vec<Entity>,
+    status: Option<Entity>,
+    text: Option<MenuText>,
+}
+
+impl BindingsMenuState {
+    fn labels(&self, world: &World) -> Vec<String> {
+        let input = world.read_resource::<InputHandler<StringBindings>>();
+        self.actions
+            .iter()
+            .map(|action| {
+                let combos: Vec<String> = input
+                    .bindings
+                    .action_bindings(action)
+                    .map(|combo| {
+                        combo
+                            .iter()
+                            .map(|button| button_label(*button))
+                            .collect::<Vec<_>>()
+                            .join(" + ")
+                    })
+                    .collect();
+                format!("{}: {}", action, combos.join(" or "))
+            })
+            .collect()
+    }
+
+    fn refresh(&self, world: &mut World, status: String) {
+        let labels = self.labels(world);
+        if let Some(text) = &self.text {
+            text.update_rows(world, &self.rows, labels, self.selected);
+            if let Some(status_entity) = self.status {
+                text.set_text(world, status_entity, status);
+            }
+        }
+    }
+
+    fn rebind(&mut self, world: &mut World, button: Button) -> String {
+        let action = &self.actions[self.selected];
+        let mut input = world.write_resource::<InputHandler<StringBindings>>();
+        if let Some(other) = bound_action(&input.bindings, button) {
+            if other != action {
+                return format!("{} is already bound to {}", button_label(button), other);
+            }
+        }
+
+        let bindings = &mut input.bindings;
+        let combos: Vec<Vec<Button>> = bindings
+            .action_bindings(action)
+            .map(|combo| combo.to_vec())
+            .collect();
+        for combo in combos {
+            bindings.remove_action_binding(action, &combo);
+        }
+        if let Err(error) = bindings.insert_action_binding(action.clone(), vec![button]) {
+            return format!("Cannot bind {}: {}", button_label(button), error);
+        }
+
+        self.changed = true;
+        format!("{} bound to {}", action, button_label(button))
+    }
+
+    fn reset(&mut self, world: &mut World) -> String {
+        let defaults = world
+            .read_resource::<UserFiles>()
+            .assets
+            .join("bindings.ron");
+        match Bindings::<StringBindings>::load(&defaults) {
+            Ok(bindings) => {
+                world
+                    .write_resource::<InputHandler<StringBindings>>()
+                    .bindings = bindings;
+                self.changed = true;
+                "Default bindings restored".to_string()
+            }
+            Err(error) => format!("Cannot read the default bindings: {}", error),
+        }
+    }
+
+    fn save(&self, world: &World) {
+        if !self.changed {
+            return;
+        }
+
+        let files = world.read_resource::<UserFiles>();
+        let input = world.read_resource::<InputHandler<StringBindings>>();
+        if let Err(error) = save_user_file(&input.bindings, &files.bindings) {
+            warn!(
+                "Failed to save the bindings to {}: {}",
+                files.bindings.display(),
+                error
+            );
+        }
+    }
+
+    fn handle_button(&mut self, world: &mut World, button: Button) -> SimpleTrans {
+        let escape = Button::Key(VirtualKeyCode::Escape);
+        let status = if self.capturing {
+            self.capturing = false;
+            if button == escape {
+                String::new()
+            } else {
+                self.rebind(world, button)
+            }
+        } else {
+            let count = self.actions.len();
+            match button {
+                Button::Key(VirtualKeyCode::Escape) => {
+                    self.save(world);
+                    return Trans::Replace(Box::new(SnakeGame));
+                }
+                Button::Key(VirtualKeyCode::Up) if count > 0 => {
+                    self.selected = (self.selected + count - 1) % count;
+                    String::new()
+                }
+                Button::Key(VirtualKeyCode::Down) if count > 0 => {
+                    self.selected = (self.selected + 1) % count;
+                    String::new()
+                }
+                Button::Key(VirtualKeyCode::Return) if count > 0 => {
+                    self.capturing = true;
+                    format!(
+                        "Press a key or button for {}, Escape to cancel",
+                        self.actions[self.selected]
+                    )
+                }
+                Button::Key(VirtualKeyCode::Delete) => self.reset(world),
+                _ => return Trans::None,
+            }
+        };
+
+        play_effect(world, MENU);
+        self.refresh(world, status);
+        Trans::None
+    }
+}
+
+impl SimpleState for BindingsMenuState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.delete_all();
+        world.insert(GameState::InMenu);
+        world.write_resource::<Music>().set_mode(MusicMode::Menu);
+
+        self.actions = world
+            .read_resource::<InputHandler<StringBindings>>()
+            .bindings
+            .actions()
+            .cloned()
+            .collect();
+        self.actions.sort();
+
+        let text = MenuText::new(world);
+        text.create(world, "bindings", -50., "Controls".to_string(), 100.);
+        let labels = self.labels(world);
+        self.rows = text.create_rows(world, "bindings_action", labels, COMPACT_ROW_HEIGHT);
+        let bottom = FIRST_ROW - (self.actions.len() as f32 + 0.5) * COMPACT_ROW_HEIGHT;
+        self.status = Some(text.create(world, "bindings_status", bottom, String::new(), 30.));
+        text.create(
+            world,
+            "bindings_hint",
+            bottom - COMPACT_ROW_HEIGHT,
+            "Up/Down: choose   Enter: rebind   Delete: restore defaults   Escape: save and back"
+                .to_string(),
+            25.,
+        );
+        self.text = Some(text);
+
+        self.refresh(world, String::new());
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) if is_close_requested(&event) => Trans::Quit,
+            StateEvent::Input(InputEvent::ButtonPressed(button)) => {
+                self.handle_button(data.world, *button)
+            }
+            _ => Trans::None,
+        }
+    }
+}
+
+fn bound_action(bindings: &Bindings<StringBindings>, button: Button) -> Option<&String> {
+    bindings.actions().find(|action| {
+        bindings
+            .action_bindings(*action)
+            .any(|combo| combo.contains(&button))
+    })
+}
+
+fn button_label(button: Button) -> String {
+    match button {
+        Button::Key(key) => format!("{:?}", key),
+        Button::Mouse(mouse_button) => format!("Mouse {:?}", mouse_button),
+        Button::Controller(controller, controller_button) => {
+            format!("Controller {} {:?}", controller, controller_button)
+        }
+        other => format!("{:?}", other),
+    }
+}
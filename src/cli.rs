@@ -0,0 +1,292 @@
+use std::path::PathBuf;
+
+use amethyst::{config::Config, Error, Result};
+use log::LevelFilter;
+
+use crate::replay::Replay;
+use crate::settings::{ControlScheme, PlayerSettings, Settings, MIN_ARENA_SIZE};
+
+pub const USAGE: &str = "Usage: snake [OPTIONS]
+
+Options:
+    --mode <solo|versus>          Play alone, or with at least two players
+    --level <NAME|FILE.ron>       Level of assets/levels, or path to a level file
+    --ruleset <NAME|FILE.ron>     Ruleset of assets/rulesets, or path to a ruleset file
+    --seed <NUMBER>               Seed of the apple positions
+    --tick-ms <MILLISECONDS>      Time between two snake moves
+    --arena <WIDTHxHEIGHT>        Size of the board in cells, walls included, at least 10x10
+    --replay <FILE.ron>           Play a recorded round
+    --record <FILE.ron>           Record every round, overwriting the file
+    --window <windowed|fullscreen|headless>
+                                  Window mode, headless only runs the rules of one round
+    --log-level <LEVEL>           off, error, warn, info, debug or trace
+    --help                        Print this message";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GameMode {
+    Solo,
+    Versus,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WindowOption {
+    Windowed,
+    Fullscreen,
+    Headless,
+}
+
+/// The options given on the command line, which override the `Settings`.
+#[derive(Default)]
+pub struct CommandLine {
+    pub help: bool,
+    pub mode: Option<GameMode>,
+    pub level: Option<String>,
+    pub ruleset: Option<String>,
+    pub seed: Option<u64>,
+    pub tick_interval_ms: Option<u64>,
+    pub arena: Option<(i32, i32)>,
+    pub replay: Option<Replay>,
+    pub record: Option<PathBuf>,
+    pub window: Option<WindowOption>,
+    pub log_level: Option<LevelFilter>,
+}
+
+impl CommandLine {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<CommandLine> {
+        let mut command_line = CommandLine::default();
+        while let Some(arg) = args.next() {
+            if arg == "--help" || arg == "-h" {
+                command_line.help = true;
+                continue;
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| Error::from_string(format!("Missing value for {}", arg)))?;
+            let invalid = || Error::from_string(format!("Invalid value for {}: {}", arg, value));
+            match arg.as_str() {
+                "--mode" => {
+                    command_line.mode = Some(match value.as_str() {
+                        "solo" => GameMode::Solo,
+                        "versus" => GameMode::Versus,
+                        _ => return Err(invalid()),
+                    })
+                }
+                "--level" => command_line.level = Some(value),
+                "--ruleset" => command_line.ruleset = Some(value),
+                "--seed" => command_line.seed = Some(value.parse().map_err(|_| invalid())?),
+                "--tick-ms" => {
+                    command_line.tick_interval_ms = Some(value.parse().map_err(|_| invalid())?)
+                }
+                "--arena" => {
+                    let mut size = value.splitn(2, 'x').map(str::parse::<i32>);
+                    match (size.next(), size.next()) {
+                        (Some(Ok(width)), Some(Ok(height)))
+                            if width >= MIN_ARENA_SIZE && height >= MIN_ARENA_SIZE =>
+                        {
+                            command_line.arena = Some((width, height))
+                        }
+                        _ => return Err(invalid()),
+                    }
+                }
+                "--replay" => command_line.replay = Some(Replay::load(&value)?),
+                "--record" => command_line.record = Some(PathBuf::from(value)),
+                "--window" => {
+                    command_line.window = Some(match value.as_str() {
+                        "windowed" => WindowOption::Windowed,
+                        "fullscreen" => WindowOption::Fullscreen,
+                        "headless" => WindowOption::Headless,
+                        _ => return Err(invalid()),
+                    })
+                }
+                "--log-level" => {
+                    command_line.log_level = Some(value.parse().map_err(|_| invalid())?)
+                }
+                _ => {
+                    return Err(Error::from_string(format!(
+                        "Unknown option {}\n\n{}",
+                        arg, USAGE
+                    )))
+                }
+            }
+        }
+        Ok(command_line)
+    }
+
+    /// Whether the game runs without a window, a graphics device or audio.
+    pub fn headless(&self) -> bool {
+        self.window == Some(WindowOption::Headless)
+    }
+
+    /// Overrides `settings` with the options. A replay brings its own settings, so the options
+    /// changing the game are ignored when one is played.
+    pub fn apply(&self, settings: &mut Settings) {
+        if let Some(replay) = &self.replay {
+            let hot_reload = settings.hot_reload;
+            *settings = replay.settings.clone();
+            settings.hot_reload = hot_reload;
+        } else {
+            match self.mode {
+                Some(GameMode::Solo) => settings.players.truncate(1),
+                Some(GameMode::Versus) => {
+                    for controls in &[ControlScheme::Wasd, ControlScheme::Arrows] {
+                        if settings.players.len() >= 2 {
+                            break;
+                        }
+                        if settings
+                            .players
+                            .iter()
+                            .all(|player| player.controls != *controls)
+                        {
+                            settings.players.push(PlayerSettings {
+                                controls: *controls,
+                                ..PlayerSettings::default()
+                            });
+                        }
+                    }
+                }
+                None => {}
+            }
+            if let Some(level) = &self.level {
+                settings.level = Some(level.clone());
+            }
+            if let Some(ruleset) = &self.ruleset {
+                settings.ruleset = Some(ruleset.clone());
+            }
+            if let Some(tick_interval_ms) = self.tick_interval_ms {
+                settings.tick_interval_ms = Some(tick_interval_ms);
+            }
+            if let Some((width, height)) = self.arena {
+                settings.arena_width = width;
+                settings.arena_height = height;
+            }
+        }
+
+        match self.window {
+            Some(WindowOption::Windowed) | Some(WindowOption::Headless) => {
+                settings.fullscreen = false
+            }
+            Some(WindowOption::Fullscreen) => settings.fullscreen = true,
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CommandLine> {
+        CommandLine::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn error(args: &[&str]) -> String {
+        parse(args)
+            .err()
+            .expect("the arguments should be rejected")
+            .to_string()
+    }
+
+    #[test]
+    fn parses_every_option() {
+        let command_line = parse(&[
+            "--mode",
+            "versus",
+            "--level",
+            "maze",
+            "--ruleset",
+            "arcade",
+            "--seed",
+            "42",
+            "--tick-ms",
+            "120",
+            "--arena",
+            "30x20",
+            "--record",
+            "round.ron",
+            "--window",
+            "headless",
+            "--log-level",
+            "debug",
+        ])
+        .unwrap();
+
+        assert!(!command_line.help);
+        assert_eq!(command_line.mode, Some(GameMode::Versus));
+        assert_eq!(command_line.level.as_deref(), Some("maze"));
+        assert_eq!(command_line.ruleset.as_deref(), Some("arcade"));
+        assert_eq!(command_line.seed, Some(42));
+        assert_eq!(command_line.tick_interval_ms, Some(120));
+        assert_eq!(command_line.arena, Some((30, 20)));
+        assert_eq!(command_line.record, Some(PathBuf::from("round.ron")));
+        assert_eq!(command_line.window, Some(WindowOption::Headless));
+        assert!(command_line.headless());
+        assert_eq!(command_line.log_level, Some(LevelFilter::Debug));
+    }
+
+    #[test]
+    fn no_arguments_override_nothing() {
+        let command_line = parse(&[]).unwrap();
+
+        assert!(!command_line.help);
+        assert_eq!(command_line.mode, None);
+        assert_eq!(command_line.arena, None);
+        assert!(!command_line.headless());
+    }
+
+    #[test]
+    fn help_takes_no_value() {
+        let command_line = parse(&["--help", "--seed", "7"]).unwrap();
+
+        assert!(command_line.help);
+        assert_eq!(command_line.seed, Some(7));
+        assert!(parse(&["-h"]).unwrap().help);
+    }
+
+    #[test]
+    fn rejects_unknown_options() {
+        assert!(error(&["--colour", "red"]).starts_with("Unknown option --colour"));
+    }
+
+    #[test]
+    fn rejects_missing_values() {
+        assert_eq!(error(&["--seed"]), "Missing value for --seed");
+        assert_eq!(
+            error(&["--mode", "solo", "--arena"]),
+            "Missing value for --arena"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert_eq!(error(&["--mode", "coop"]), "Invalid value for --mode: coop");
+        assert_eq!(error(&["--seed", "-1"]), "Invalid value for --seed: -1");
+        assert_eq!(
+            error(&["--window", "tiny"]),
+            "Invalid value for --window: tiny"
+        );
+        assert_eq!(error(&["--arena", "30"]), "Invalid value for --arena: 30");
+        assert_eq!(
+            error(&["--arena", "30xten"]),
+            "Invalid value for --arena: 30xten"
+        );
+    }
+
+    #[test]
+    fn rejects_arenas_smaller_than_the_settings_menu_allows() {
+        let too_small = MIN_ARENA_SIZE - 1;
+        for arena in &[
+            format!("{}x{}", too_small, MIN_ARENA_SIZE),
+            format!("{}x{}", MIN_ARENA_SIZE, too_small),
+        ] {
+            assert_eq!(
+                error(&["--arena", arena]),
+                format!("Invalid value for --arena: {}", arena)
+            );
+        }
+
+        let smallest = format!("{}x{}", MIN_ARENA_SIZE, MIN_ARENA_SIZE);
+        let command_line = parse(&["--arena", &smallest]).unwrap();
+        assert_eq!(command_line.arena, Some((MIN_ARENA_SIZE, MIN_ARENA_SIZE)));
+    }
+}
@@ -1,8 +1,17 @@
 use amethyst::ecs::prelude::*;
 
-#[derive(Default)]
-pub struct AppleComponent {}
+use crate::fruits::AppleBehaviour;
+
+/// An apple lying on the board, `fruit` being its index in the `FruitTable`.
+pub struct AppleComponent {
+    pub fruit: usize,
+    /// The tick the fruit appeared on, which the behaviour counts from.
+    pub spawned_at: u64,
+    pub behaviour: AppleBehaviour,
+    /// Whether the apple moves elsewhere once eaten, instead of being removed.
+    pub respawn: bool,
+}
 
 impl Component for AppleComponent {
-    type Storage = NullStorage<Self>;
+    type Storage = DenseVecStorage<Self>;
 }
@@ -0,0 +1,12 @@
+use amethyst::{core::math::Vector2, ecs::prelude::*};
+
+#[derive(Default)]
+pub struct CameraRigComponent {
+    pub focus: Option<Vector2<f32>>,
+    pub shake_remaining: f32,
+    pub has_shaken: bool,
+}
+
+impl Component for CameraRigComponent {
+    type Storage = DenseVecStorage<Self>;
+}
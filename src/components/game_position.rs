@@ -15,22 +15,15 @@ impl GamePositionComponent {
         GamePositionComponent { position }
     }
 
-    pub fn to_transform(&self) -> Transform {
-        let mut transform = Transform::default();
-        transform.set_translation_xyz(
-            (self.position.x * 32) as f32,
-            (self.position.y * 32) as f32,
-            0.5,
-        );
-
-        transform
+    pub fn to_transform(&self, tile_size: f32) -> Transform {
+        self.to_transform_with_z(tile_size, 0.5)
     }
 
-    pub fn to_transform_with_z(&self, z: f32) -> Transform {
+    pub fn to_transform_with_z(&self, tile_size: f32, z: f32) -> Transform {
         let mut transform = Transform::default();
         transform.set_translation_xyz(
-            (self.position.x * 32) as f32,
-            (self.position.y * 32) as f32,
+            self.position.x as f32 * tile_size,
+            self.position.y as f32 * tile_size,
             z,
         );
 
@@ -1,9 +1,17 @@
 pub use apple::AppleComponent;
+pub use camera_rig::CameraRigComponent;
 pub use game_position::GamePositionComponent;
-pub use snake_head::SnakeHeadComponent;
-pub use snake_part::{SnakePartComponent, SnakePartType};
+pub use particle::{ParticleComponent, ParticleEmitterComponent};
+pub use power_up::PowerUpComponent;
+pub use snake::SnakeComponent;
+pub use sprite_animation::SpriteAnimationComponent;
+pub use timed_effect::TimedEffectComponent;
 
 mod apple;
+mod camera_rig;
 mod game_position;
-mod snake_head;
-mod snake_part;
+mod particle;
+mod power_up;
+mod snake;
+mod sprite_animation;
+mod timed_effect;
@@ -0,0 +1,27 @@
+use amethyst::{core::math::Vector2, ecs::prelude::*};
+
+pub struct ParticleEmitterComponent {
+    pub preset: String,
+}
+
+impl Component for ParticleEmitterComponent {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl ParticleEmitterComponent {
+    pub fn new(preset: &str) -> Self {
+        ParticleEmitterComponent {
+            preset: preset.to_string(),
+        }
+    }
+}
+
+pub struct ParticleComponent {
+    pub preset: String,
+    pub velocity: Vector2<f32>,
+    pub age: f32,
+}
+
+impl Component for ParticleComponent {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,10 @@
+use amethyst::ecs::prelude::*;
+
+/// A power-up lying on the board, `kind` being its index in the `PowerUpTable`.
+pub struct PowerUpComponent {
+    pub kind: usize,
+}
+
+impl Component for PowerUpComponent {
+    type Storage = DenseVecStorage<Self>;
+}
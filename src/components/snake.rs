@@ -0,0 +1,242 @@
+use std::collections::VecDeque;
+
+use amethyst::ecs::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::components::GamePositionComponent;
+use crate::occupancy_grid::{Cell, OccupancyGrid};
+use crate::snake::{Direction, GameRng};
+
+pub struct SnakeComponent {
+    pub player: usize,
+    pub next_direction: Direction,
+    pub body: VecDeque<glm::IVec2>,
+    pub segments: VecDeque<Entity>,
+    pub head_serial: u64,
+    pub previous_tail: Option<glm::IVec2>,
+    pub score: u32,
+    /// The segments the snake still has to gain, one per move, or to lose when negative.
+    pub pending_growth: i32,
+    /// How many times the snake can still die, counting the current life.
+    pub lives: u32,
+}
+
+impl Component for SnakeComponent {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl SnakeComponent {
+    pub fn new(player: usize, direction: Direction, lives: u32) -> Self {
+        SnakeComponent {
+            player,
+            next_direction: direction,
+            body: VecDeque::new(),
+            segments: VecDeque::new(),
+            head_serial: 0,
+            previous_tail: None,
+            score: 0,
+            pending_growth: 0,
+            lives,
+        }
+    }
+
+    pub fn head(&self) -> glm::IVec2 {
+        self.body[0]
+    }
+
+    /// The direction the snake moved in on its last move, rather than the one it was given
+    /// since. It is `None` for a snake reduced to its head.
+    pub fn heading(&self, grid: &OccupancyGrid) -> Option<Direction> {
+        let neck = grid.unwrap_near(self.body.get(1)?, &self.head());
+        let offset = self.head() - neck;
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Right,
+            Direction::Left,
+        ]
+        .iter()
+        .find(|direction| direction.offset() == offset)
+        .cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.body.len()
+    }
+
+    pub fn previous_position(&self, index: usize) -> glm::IVec2 {
+        match self.body.get(index + 1) {
+            Some(position) => *position,
+            None => self.previous_tail.unwrap_or(self.body[index]),
+        }
+    }
+
+    pub fn segment_serial(&self, index: usize) -> u64 {
+        self.head_serial - index as u64
+    }
+
+    pub fn segment_index(&self, serial: u64) -> usize {
+        (self.head_serial - serial) as usize
+    }
+
+    pub fn push_head(&mut self, position: glm::IVec2, segment: Entity) {
+        self.body.push_front(position);
+        self.segments.push_front(segment);
+        self.head_serial += 1;
+    }
+
+    pub fn pop_tail(&mut self) -> Option<(glm::IVec2, Entity)> {
+        match (self.body.pop_back(), self.segments.pop_back()) {
+            (Some(position), Some(segment)) => Some((position, segment)),
+            _ => None,
+        }
+    }
+
+    /// Removes `segments` segments from the tail, keeping at least the head and the tail.
+    pub fn shrink(
+        &mut self,
+        snake_entity: Entity,
+        segments: usize,
+        entities: &Entities<'_>,
+        grid: &mut OccupancyGrid,
+    ) {
+        for _ in 0..segments {
+            if self.len() <= 2 {
+                break;
+            }
+            let tail_cell = Cell::Snake {
+                snake: snake_entity,
+                segment: self.segment_serial(self.len() - 1),
+            };
+            if let Some((position, segment)) = self.pop_tail() {
+                if grid.get(&position) == tail_cell {
+                    grid.clear(&position);
+                }
+                entities.delete(segment).unwrap();
+            }
+        }
+        self.previous_tail = None;
+    }
+
+    /// Empties the cells of the grid still owned by the segments of the snake.
+    pub fn release_cells(&self, snake_entity: Entity, grid: &mut OccupancyGrid) {
+        for index in 0..self.len() {
+            let cell = Cell::Snake {
+                snake: snake_entity,
+                segment: self.segment_serial(index),
+            };
+            if grid.get(&self.body[index]) == cell {
+                grid.clear(&self.body[index]);
+            }
+        }
+    }
+
+    /// Takes a life from the snake, whose cells were released, and puts it back on the board.
+    /// Returns `false` when it has no life left or no room to come back, which ends the game.
+    pub fn lose_life(
+        &mut self,
+        snake_entity: Entity,
+        entities: &Entities<'_>,
+        positions: &mut WriteStorage<'_, GamePositionComponent>,
+        grid: &mut OccupancyGrid,
+        rng: &mut GameRng,
+    ) -> bool {
+        if self.lives <= 1 || !self.respawn(snake_entity, entities, positions, grid, rng) {
+            return false;
+        }
+        self.lives -= 1;
+        true
+    }
+
+    /// Moves the snake with at most three segments to a free row picked with the `GameRng`,
+    /// heading right. Returns `false` when there is no room left for it.
+    fn respawn(
+        &mut self,
+        snake_entity: Entity,
+        entities: &Entities<'_>,
+        positions: &mut WriteStorage<'_, GamePositionComponent>,
+        grid: &mut OccupancyGrid,
+        rng: &mut GameRng,
+    ) -> bool {
+        let length = self.len().min(3) as i32;
+        let heads: Vec<glm::IVec2> = grid
+            .free_cells()
+            .filter(|head| (1..length).all(|x| grid.is_free(&(head - glm::vec2(x, 0)))))
+            .collect();
+        let head = match heads.choose(&mut rng.0) {
+            Some(head) => *head,
+            None => return false,
+        };
+
+        while self.len() > length as usize {
+            if let Some((_, segment)) = self.pop_tail() {
+                entities.delete(segment).unwrap();
+            }
+        }
+        let mut segments = vec![];
+        while let Some((_, segment)) = self.pop_tail() {
+            segments.push(segment);
+        }
+        for (x, segment) in segments.into_iter().enumerate() {
+            let position = head - glm::vec2(length - 1 - x as i32, 0);
+            if let Some(segment_position) = positions.get_mut(segment) {
+                segment_position.position = position;
+            }
+            self.push_head(position, segment);
+            grid.set(
+                &position,
+                Cell::Snake {
+                    snake: snake_entity,
+                    segment: self.head_serial,
+                },
+            );
+        }
+
+        self.next_direction = Direction::Right;
+        self.pending_growth = 0;
+        self.previous_tail = None;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use amethyst::ecs::{Builder, World, WorldExt};
+
+    use super::*;
+
+    #[test]
+    fn serials_follow_the_moves() {
+        let mut world = World::new();
+        let segments: Vec<Entity> = (0..3).map(|_| world.create_entity().build()).collect();
+        let mut snake = SnakeComponent::new(0, Direction::Right, 1);
+        for (x, segment) in segments.iter().enumerate() {
+            snake.push_head(glm::vec2(x as i32, 0), *segment);
+        }
+        assert_eq!(snake.head_serial, 3);
+        assert_eq!(snake.segment_serial(0), 3);
+        assert_eq!(snake.segment_serial(2), 1);
+
+        // Moving recycles the tail as the new head without changing the serials of the others.
+        let (_, tail) = snake.pop_tail().unwrap();
+        snake.push_head(glm::vec2(3, 0), tail);
+        assert_eq!(snake.head(), glm::vec2(3, 0));
+        assert_eq!(snake.segment_serial(0), 4);
+        assert_eq!(snake.segment_serial(2), 2);
+        for index in 0..snake.len() {
+            assert_eq!(snake.segment_index(snake.segment_serial(index)), index);
+        }
+    }
+
+    #[test]
+    fn previous_position_falls_back_to_the_popped_tail() {
+        let mut world = World::new();
+        let mut snake = SnakeComponent::new(0, Direction::Right, 1);
+        snake.push_head(glm::vec2(1, 1), world.create_entity().build());
+        snake.push_head(glm::vec2(2, 1), world.create_entity().build());
+        assert_eq!(snake.previous_position(0), glm::vec2(1, 1));
+        assert_eq!(snake.previous_position(1), glm::vec2(1, 1));
+        snake.previous_tail = Some(glm::vec2(0, 1));
+        assert_eq!(snake.previous_position(1), glm::vec2(0, 1));
+    }
+}
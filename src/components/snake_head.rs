@@ -1,8 +0,0 @@
-use amethyst::ecs::prelude::*;
-
-#[derive(Default)]
-pub struct SnakeHeadComponent {}
-
-impl Component for SnakeHeadComponent {
-    type Storage = NullStorage<Self>;
-}
@@ -0,0 +1,23 @@
+use amethyst::ecs::prelude::*;
+
+pub struct SpriteAnimationComponent {
+    pub clip: String,
+    pub elapsed: f32,
+}
+
+impl Component for SpriteAnimationComponent {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl SpriteAnimationComponent {
+    pub fn new(clip: &str) -> Self {
+        SpriteAnimationComponent {
+            clip: clip.to_string(),
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn is_playing(&self, clip: &str) -> bool {
+        self.clip == clip
+    }
+}
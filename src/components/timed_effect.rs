@@ -0,0 +1,18 @@
+use amethyst::ecs::prelude::*;
+
+use crate::power_ups::Effect;
+
+/// Lives on its own entity and applies `effect` to the `target` snake until `remaining_ticks`
+/// runs out, so a snake can have any number of effects at once. `started_at` is the tick the
+/// effect started or was restarted on, which does not count towards its duration.
+pub struct TimedEffectComponent {
+    pub target: Entity,
+    pub effect: Effect,
+    pub label: String,
+    pub remaining_ticks: u64,
+    pub started_at: u64,
+}
+
+impl Component for TimedEffectComponent {
+    type Storage = DenseVecStorage<Self>;
+}
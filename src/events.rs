@@ -0,0 +1,26 @@
+use amethyst::ecs::Entity;
+
+use crate::snake::Direction;
+
+/// What happens during a round. The systems applying the rules publish these to the
+/// `EventChannel<GameEvent>` resource, and the systems reacting to them, like the score, the
+/// sounds or the particles, read them from there.
+#[derive(Clone, Debug)]
+pub enum GameEvent {
+    /// `snake` ate the apple lying on `position`, `fruit` being its index in the `FruitTable`.
+    AppleEaten {
+        snake: Entity,
+        fruit: usize,
+        position: glm::IVec2,
+    },
+    /// `snake` moved in another `direction` than on its previous move.
+    Turned { snake: Entity, direction: Direction },
+    /// `snake` gained or lost segments, and is now `length` segments long.
+    Grew { snake: Entity, length: usize },
+    /// `snake` ran into something with its head on `position`, or ate a deadly fruit there.
+    Died { snake: Entity, position: glm::IVec2 },
+    /// `snake` collected a power-up, `kind` being its index in the `PowerUpTable`.
+    PowerUpCollected { snake: Entity, kind: usize },
+    /// No apple is left on the board.
+    LevelCompleted,
+}
@@ -0,0 +1,57 @@
+use rand::{rngs::StdRng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+
+/// How an apple acts while it lies on the board.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum AppleBehaviour {
+    /// Stays on its cell until it is eaten.
+    Static,
+    /// Rots away after `lifetime_ticks` and is replaced somewhere else, fading out during the
+    /// last `warning_ticks`.
+    Expiring {
+        lifetime_ticks: u64,
+        warning_ticks: u64,
+    },
+    /// Moves to a free neighbouring cell every `every_ticks` ticks.
+    Wandering { every_ticks: u64 },
+}
+
+impl Default for AppleBehaviour {
+    fn default() -> Self {
+        AppleBehaviour::Static
+    }
+}
+
+/// A kind of fruit the apple can turn into.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Fruit {
+    pub name: String,
+    pub sprite: String,
+    /// How often the fruit shows up compared to the others.
+    pub weight: u32,
+    /// Given to the apple every time it turns into this fruit.
+    #[serde(default)]
+    pub behaviour: AppleBehaviour,
+    pub points: u32,
+    /// The segments the snake gains, or loses when negative.
+    pub growth: i32,
+    /// Eating the fruit costs the snake a life, and ends the game when it was its last one.
+    #[serde(default)]
+    pub deadly: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FruitTable {
+    pub fruits: Vec<Fruit>,
+}
+
+impl FruitTable {
+    /// The index of a fruit picked according to the weights, `None` when no fruit has a weight.
+    pub fn pick(&self, rng: &mut StdRng) -> Option<usize> {
+        let indices: Vec<usize> = (0..self.fruits.len()).collect();
+        indices
+            .choose_weighted(rng, |index| self.fruits[*index].weight)
+            .ok()
+            .copied()
+    }
+}
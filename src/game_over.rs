@@ -1,12 +1,15 @@
 use amethyst::{
-    assets::Loader,
     core::*,
     prelude::*,
-    ui::{Anchor, TtfFormat, UiText, UiTransform},
+    ui::{Anchor, UiText, UiTransform},
     GameData, SimpleState, SimpleTrans, StateData,
 };
+use log::info;
 
+use crate::audio::{play_effect, Music, MusicMode, MENU};
 use crate::snake::SnakeGame;
+use crate::stats::Stats;
+use crate::theme::{load_font, Themes};
 
 pub struct GameOverState;
 
@@ -14,12 +17,20 @@ impl SimpleState for GameOverState {
     fn on_start(&mut self, _data: StateData<'_, GameData<'_, '_>>) {
         _data.world.delete_all();
 
-        let font = _data.world.read_resource::<Loader>().load(
-            "chicken.ttf",
-            TtfFormat,
-            (),
-            &_data.world.read_resource(),
-        );
+        _data
+            .world
+            .write_resource::<Music>()
+            .set_mode(MusicMode::GameOver);
+        play_effect(_data.world, MENU);
+        info!("Game over: {}", *_data.world.read_resource::<Stats>());
+
+        let font = load_font(_data.world);
+        let colors = _data
+            .world
+            .read_resource::<Themes>()
+            .selected
+            .colors
+            .clone();
         let game_over = UiTransform::new(
             "game_over".to_string(),
             Anchor::TopMiddle,
@@ -37,7 +48,7 @@ impl SimpleState for GameOverState {
             .with(UiText::new(
                 font,
                 "Game Over".to_string(),
-                [1., 1., 1., 1.],
+                colors.text,
                 150.,
             ))
             .build();
@@ -0,0 +1,301 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use amethyst::{
+    config::Config,
+    ecs::Entity,
+    input::{Bindings, InputHandler, StringBindings},
+    prelude::*,
+    ui::UiText,
+    window::DisplayConfig,
+    winit::{dpi::LogicalSize, Window},
+};
+use log::info;
+
+use crate::cli::CommandLine;
+use crate::fruits::FruitTable;
+use crate::level::Level;
+use crate::menu::MenuText;
+use crate::particles::ParticlePresets;
+use crate::power_ups::PowerUpTable;
+use crate::ruleset::Ruleset;
+use crate::settings::{Settings, UserFiles};
+use crate::theme::{insert_themes, load_valid_themes, Themes};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Watches the files of the `assets` and `user` folders by polling their modification times, so
+/// that they can be applied while the game runs when `Settings::hot_reload` is enabled.
+pub struct HotReload {
+    folders: Vec<PathBuf>,
+    modified: HashMap<PathBuf, SystemTime>,
+    last_poll: Instant,
+    /// The error of every file that failed to reload, until it is fixed.
+    errors: BTreeMap<PathBuf, String>,
+    label: Option<Entity>,
+}
+
+impl HotReload {
+    pub fn new(files: &UserFiles) -> Self {
+        let mut folders = vec![files.assets.clone()];
+        if let Some(user) = files.settings.parent() {
+            folders.push(user.to_path_buf());
+        }
+        let modified = scan(&folders);
+        HotReload {
+            folders,
+            modified,
+            last_poll: Instant::now(),
+            errors: BTreeMap::new(),
+            label: None,
+        }
+    }
+
+    /// The files created, modified or deleted since the last poll.
+    fn changed_files(&mut self) -> Vec<PathBuf> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return vec![];
+        }
+        self.last_poll = Instant::now();
+
+        let modified = scan(&self.folders);
+        let mut changed: Vec<PathBuf> = modified
+            .iter()
+            .filter(|(path, time)| self.modified.get(*path) != Some(time))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(
+            self.modified
+                .keys()
+                .filter(|path| !modified.contains_key(*path))
+                .cloned(),
+        );
+        self.modified = modified;
+        changed
+    }
+
+    fn error_text(&self) -> String {
+        self.errors
+            .iter()
+            .map(|(path, error)| format!("{}: {}", path.display(), error))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+fn scan(folders: &[PathBuf]) -> HashMap<PathBuf, SystemTime> {
+    let mut modified = HashMap::new();
+    let mut pending = folders.to_vec();
+    while let Some(folder) = pending.pop() {
+        let entries = match fs::read_dir(&folder) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(time) = entry.metadata().and_then(|metadata| metadata.modified()) {
+                modified.insert(path, time);
+            }
+        }
+    }
+    modified
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Reload {
+    Bindings,
+    Display,
+    Particles,
+    PowerUps,
+    Fruits,
+    /// Files only read when a round starts.
+    Round,
+}
+
+fn reload_kind(files: &UserFiles, path: &Path) -> Option<Reload> {
+    if path == files.settings {
+        return Some(Reload::Round);
+    }
+    if path == files.bindings {
+        return Some(Reload::Bindings);
+    }
+
+    let relative = path.strip_prefix(&files.assets).ok()?;
+    let first = relative.components().next()?.as_os_str().to_str()?;
+    match first {
+        "bindings.ron" => Some(Reload::Bindings),
+        "display_config.ron" => Some(Reload::Display),
+        "particles.ron" => Some(Reload::Particles),
+        "power_ups.ron" => Some(Reload::PowerUps),
+        "fruits.ron" => Some(Reload::Fruits),
+        "settings.ron" | "themes" | "levels" | "rulesets" => Some(Reload::Round),
+        _ => None,
+    }
+}
+
+/// Creates the text showing the reload errors. It is deleted with the other entities when a new
+/// round starts.
+pub fn init_error_label(world: &mut World) {
+    if !world.has_value::<HotReload>() {
+        return;
+    }
+
+    let text = world.read_resource::<HotReload>().error_text();
+    let label = MenuText::new(world).create(world, "hot_reload_errors", -20., text, 20.);
+    world.write_resource::<HotReload>().label = Some(label);
+}
+
+/// Applies the files changed since the last poll. Returns whether the round has to restart to
+/// take them into account.
+pub fn apply_changes(world: &mut World) -> bool {
+    if !world.has_value::<HotReload>() {
+        return false;
+    }
+
+    let changed = world.write_resource::<HotReload>().changed_files();
+    if changed.is_empty() {
+        return false;
+    }
+
+    let files = world.read_resource::<UserFiles>().clone();
+    let mut restart = false;
+    for path in changed {
+        let kind = match reload_kind(&files, &path) {
+            Some(kind) => kind,
+            None => continue,
+        };
+        info!("Reloading {}", path.display());
+
+        let result = match kind {
+            Reload::Bindings => reload_bindings(world, &files),
+            Reload::Display => reload_display(world, &files),
+            Reload::Particles => reload_particles(world, &files),
+            Reload::PowerUps => reload_power_ups(world, &files),
+            Reload::Fruits => reload_fruits(world, &files),
+            Reload::Round => reload_round(world, &files),
+        };
+
+        let mut hot_reload = world.write_resource::<HotReload>();
+        match result {
+            Ok(()) => {
+                hot_reload.errors.remove(&path);
+                restart |= kind == Reload::Round;
+            }
+            Err(error) => {
+                hot_reload.errors.insert(path, error);
+            }
+        }
+    }
+
+    let (label, text) = {
+        let hot_reload = world.read_resource::<HotReload>();
+        (hot_reload.label, hot_reload.error_text())
+    };
+    if let Some(label) = label {
+        if let Some(ui_text) = world.write_storage::<UiText>().get_mut(label) {
+            ui_text.text = text;
+        }
+    }
+    restart
+}
+
+/// The user file if there is one, as it overrides the one in the `assets` folder.
+fn effective_file(user: &Path, assets: &Path, name: &str) -> PathBuf {
+    if user.exists() {
+        user.to_path_buf()
+    } else {
+        assets.join(name)
+    }
+}
+
+fn reload_bindings(world: &mut World, files: &UserFiles) -> Result<(), String> {
+    let path = effective_file(&files.bindings, &files.assets, "bindings.ron");
+    let bindings = Bindings::<StringBindings>::load(path).map_err(|error| error.to_string())?;
+    world
+        .write_resource::<InputHandler<StringBindings>>()
+        .bindings = bindings;
+    Ok(())
+}
+
+fn reload_display(world: &mut World, files: &UserFiles) -> Result<(), String> {
+    let config = DisplayConfig::load(files.assets.join("display_config.ron"))
+        .map_err(|error| error.to_string())?;
+    if let Some(window) = world.try_fetch::<Window>() {
+        window.set_title(&config.title);
+        if let Some((width, height)) = config.dimensions {
+            window.set_inner_size(LogicalSize::new(width.into(), height.into()));
+        }
+    }
+    Ok(())
+}
+
+fn reload_particles(world: &mut World, files: &UserFiles) -> Result<(), String> {
+    let presets = ParticlePresets::load(files.assets.join("particles.ron"))
+        .map_err(|error| error.to_string())?;
+    world
+        .read_resource::<Themes>()
+        .validate(
+            &presets,
+            &world.read_resource::<PowerUpTable>(),
+            &world.read_resource::<FruitTable>(),
+        )
+        .map_err(|error| error.to_string())?;
+    world.insert(presets);
+    Ok(())
+}
+
+fn reload_power_ups(world: &mut World, files: &UserFiles) -> Result<(), String> {
+    let table = PowerUpTable::load(files.assets.join("power_ups.ron"))
+        .map_err(|error| error.to_string())?;
+    world
+        .read_resource::<Themes>()
+        .validate(
+            &world.read_resource::<ParticlePresets>(),
+            &table,
+            &world.read_resource::<FruitTable>(),
+        )
+        .map_err(|error| error.to_string())?;
+    world.insert(table);
+    Ok(())
+}
+
+fn reload_fruits(world: &mut World, files: &UserFiles) -> Result<(), String> {
+    let table =
+        FruitTable::load(files.assets.join("fruits.ron")).map_err(|error| error.to_string())?;
+    world
+        .read_resource::<Themes>()
+        .validate(
+            &world.read_resource::<ParticlePresets>(),
+            &world.read_resource::<PowerUpTable>(),
+            &table,
+        )
+        .map_err(|error| error.to_string())?;
+    world.insert(table);
+    Ok(())
+}
+
+fn reload_round(world: &mut World, files: &UserFiles) -> Result<(), String> {
+    let path = effective_file(&files.settings, &files.assets, "settings.ron");
+    let mut settings = Settings::load(path).map_err(|error| error.to_string())?;
+    // Turning hot reload off only takes effect on the next launch.
+    settings.hot_reload = true;
+    if let Some(command_line) = world.try_fetch::<CommandLine>() {
+        command_line.apply(&mut settings);
+    }
+    let level =
+        Level::load_selected(&files.assets, &settings).map_err(|error| error.to_string())?;
+    let ruleset =
+        Ruleset::load_selected(&files.assets, &settings).map_err(|error| error.to_string())?;
+    let themes = load_valid_themes(world, &settings).map_err(|error| error.to_string())?;
+
+    // Nothing is changed before everything loaded, so that a failed reload keeps the round as is.
+    world.insert(settings);
+    insert_themes(world, themes);
+    world.insert(level);
+    world.insert(ruleset);
+    Ok(())
+}
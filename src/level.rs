@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use amethyst::config::{Config, ConfigError};
+use serde::{Deserialize, Serialize};
+
+use crate::occupancy_grid::{Cell, OccupancyGrid};
+use crate::settings::Settings;
+
+/// Obstacles placed on the board on top of the border walls, loaded from `assets/levels`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Level {
+    pub walls: Vec<(i32, i32)>,
+    /// Apples placed on the board when the round starts, gone once eaten.
+    #[serde(default)]
+    pub apples: Vec<(i32, i32)>,
+}
+
+impl Level {
+    /// Loads the level named in the `Settings`, or the empty level when there is none. A name
+    /// ending with `.ron` is the path of a level file rather than a level of `assets/levels`.
+    pub fn load_selected(assets: &Path, settings: &Settings) -> Result<Level, ConfigError> {
+        match &settings.level {
+            Some(path) if path.ends_with(".ron") => Level::load(path),
+            Some(name) => Level::load(assets.join("levels").join(format!("{}.ron", name))),
+            None => Ok(Level::default()),
+        }
+    }
+
+    /// Adds the walls of the level to `grid`, ignoring the ones outside of it.
+    pub fn place_walls(&self, grid: &mut OccupancyGrid) {
+        for &(x, y) in &self.walls {
+            let position = glm::vec2(x, y);
+            if grid.contains(&position) {
+                grid.set(&position, Cell::Wall);
+            }
+        }
+    }
+}
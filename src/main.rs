@@ -1,48 +1,199 @@
 extern crate nalgebra_glm as glm;
 
 use amethyst::{
+    assets::AssetStorage,
+    audio::AudioBundle,
+    config::Config,
     core::transform::TransformBundle,
-    input::{InputBundle, StringBindings},
+    input::{Bindings, InputBundle, StringBindings},
     prelude::*,
     renderer::{
         plugins::{RenderFlat2D, RenderToWindow},
+        resources::Tint,
         types::DefaultBackend,
-        RenderingBundle,
+        RenderingBundle, SpriteRender, SpriteSheet, Texture,
     },
     ui::{RenderUi, UiBundle},
     utils::application_root_dir,
+    window::DisplayConfig,
+    LoggerConfig,
 };
 
+mod achievements;
+mod animation;
+mod audio;
+mod bindings_menu;
+mod cli;
 mod components;
+mod events;
+mod fruits;
 mod game_over;
+mod hot_reload;
+mod level;
+mod menu;
+mod occupancy_grid;
+mod particles;
+mod power_ups;
+mod replay;
+mod ruleset;
+mod settings;
+mod settings_menu;
 mod snake;
+mod sprite_sheet;
+mod stats;
 mod systems;
+mod theme;
+mod victory;
 
 fn main() -> amethyst::Result<()> {
-    amethyst::start_logger(Default::default());
+    let command_line = cli::CommandLine::parse(std::env::args().skip(1))?;
+    if command_line.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    let mut logger_config = LoggerConfig::default();
+    if let Some(log_level) = command_line.log_level {
+        logger_config.level_filter = log_level;
+    }
+    amethyst::start_logger(logger_config);
 
     let app_root = application_root_dir()?;
 
     let resources = app_root.join("assets");
-    let display_config = resources.join("display_config.ron");
-    let bindings_config = resources.join("bindings.ron");
+    let display_config = DisplayConfig::load(resources.join("display_config.ron"))?;
+    let user_files = settings::UserFiles::new(&app_root);
+    let mut settings = settings::Settings::load_with_overrides(&user_files)?;
+    command_line.apply(&mut settings);
+    let themes = theme::Themes::load(&resources, &settings)?;
+    let level = level::Level::load_selected(&resources, &settings)?;
+    let ruleset = ruleset::Ruleset::load_selected(&resources, &settings)?;
+    let headless = command_line.headless();
+    let hot_reload = if settings.hot_reload && !headless {
+        Some(hot_reload::HotReload::new(&user_files))
+    } else {
+        None
+    };
+    let audio_config = audio::AudioConfig::load(resources.join("audio.ron"))?;
+    let particle_presets = particles::ParticlePresets::load(resources.join("particles.ron"))?;
+    let power_up_table = power_ups::PowerUpTable::load(resources.join("power_ups.ron"))?;
+    let fruit_table = fruits::FruitTable::load(resources.join("fruits.ron"))?;
+    themes.validate(&particle_presets, &power_up_table, &fruit_table)?;
+    let sprite_metadata = themes.selected.metadata.clone();
+    let clear_color = themes.selected.colors.background;
+
+    let bindings: Bindings<StringBindings> =
+        settings::load_user_file(&user_files.bindings, &resources.join("bindings.ron"))?;
+
+    let game_data = if headless {
+        // Without a window there is nothing to draw, hear or press, so only the rules run.
+        with_rule_systems(GameDataBuilder::default(), &[]).with_bundle(TransformBundle::new())?
+    } else {
+        let input_bundle = InputBundle::<StringBindings>::new().with_bindings(bindings);
+        let builder = GameDataBuilder::default()
+            .with_bundle(
+                RenderingBundle::<DefaultBackend>::new()
+                    .with_plugin(
+                        RenderToWindow::from_config(display_config).with_clear(clear_color),
+                    )
+                    .with_plugin(RenderFlat2D::default())
+                    .with_plugin(RenderUi::default()),
+            )?
+            .with_bundle(input_bundle)?
+            .with_bundle(AudioBundle::default())?
+            .with(systems::InputSystem::default(), "snake_input", &[])
+            .with(
+                systems::MouseSteeringSystem::default(),
+                "mouse_steering",
+                &["snake_input"],
+            );
+        with_rule_systems(builder, &["mouse_steering"])
+            .with(systems::HudSystem, "hud", &["timed_effects", "score"])
+            .with(
+                systems::TransformPositionsSystem,
+                "transform_position",
+                &["timed_effects"],
+            )
+            .with(
+                systems::SnakeInterpolationSystem,
+                "snake_interpolation",
+                &["transform_position"],
+            )
+            .with(
+                systems::SnakeRenderSystem,
+                "snake_render",
+                &["snake_interpolation"],
+            )
+            .with_system_desc(
+                systems::SnakeAnimationSystemDesc::default(),
+                "snake_animation",
+                &["snake_render"],
+            )
+            .with(
+                systems::SpriteAnimationSystem,
+                "sprite_animation",
+                &["snake_animation"],
+            )
+            .with_system_desc(
+                systems::ParticleTriggerSystemDesc::default(),
+                "particle_trigger",
+                &["apple_handler"],
+            )
+            .with(systems::ParticleSystem, "particles", &["particle_trigger"])
+            .with_system_desc(
+                systems::SoundEffectsSystemDesc::default(),
+                "sound_effects",
+                &["apple_handler"],
+            )
+            .with(systems::MusicSystem::default(), "music", &[])
+            .with(systems::CameraSystem, "camera", &["snake_interpolation"])
+            .with_bundle(TransformBundle::new().with_dep(&[
+                "sprite_animation",
+                "particles",
+                "camera",
+            ]))?
+            .with_bundle(UiBundle::<StringBindings>::new())?
+    };
 
-    let input_bundle =
-        InputBundle::<StringBindings>::new().with_bindings_from_file(bindings_config)?;
+    let mut builder = Application::build(resources, snake::SnakeGame)?
+        .with_resource(settings)
+        .with_resource(sprite_metadata)
+        .with_resource(particle_presets)
+        .with_resource(power_up_table)
+        .with_resource(fruit_table)
+        .with_resource(audio_config)
+        .with_resource(themes)
+        .with_resource(snake::WindowMode::default())
+        .with_resource(level)
+        .with_resource(ruleset)
+        .with_resource(user_files)
+        .with_resource(command_line);
+    if let Some(hot_reload) = hot_reload {
+        builder = builder.with_resource(hot_reload);
+    }
+    if headless {
+        // The sprite sheets are still loaded, as the rules attach sprites, but never processed.
+        builder = builder
+            .with_resource(AssetStorage::<Texture>::default())
+            .with_resource(AssetStorage::<SpriteSheet>::default())
+            .register::<SpriteRender>()
+            .register::<Tint>();
+    }
+    let mut game = builder.build(game_data)?;
+    game.run();
 
-    let game_data = GameDataBuilder::default()
-        .with_bundle(
-            RenderingBundle::<DefaultBackend>::new()
-                .with_plugin(
-                    RenderToWindow::from_config_path(display_config)?
-                        .with_clear([0.3, 0.3, 0.3, 1.0]),
-                )
-                .with_plugin(RenderFlat2D::default())
-                .with_plugin(RenderUi::default()),
-        )?
-        .with_bundle(input_bundle)?
-        .with(systems::InputSystem, "snake_input", &[])
-        .with(systems::MoveSnakeSystem, "snake_move", &["snake_input"])
+    Ok(())
+}
+
+/// Adds the systems applying the rules of the game, which run after the `input_systems` and
+/// whether there is a window or not.
+fn with_rule_systems<'a, 'b>(
+    builder: GameDataBuilder<'a, 'b>,
+    input_systems: &[&str],
+) -> GameDataBuilder<'a, 'b> {
+    builder
+        .with(systems::ReplaySystem, "replay", input_systems)
+        .with(systems::MoveSnakeSystem, "snake_move", &["replay"])
         .with(
             systems::SnakeCollisionSystem,
             "snake_collision",
@@ -54,20 +205,38 @@ fn main() -> amethyst::Result<()> {
             &["snake_collision"],
         )
         .with(
-            systems::TransformPositionsSystem,
-            "transform_position",
+            systems::AppleBehaviourSystem::default(),
+            "apple_behaviour",
             &["apple_handler"],
         )
         .with(
-            systems::SnakeRenderSystem,
-            "snake_render",
-            &["transform_position"],
+            systems::PowerUpSystem::default(),
+            "power_ups",
+            &["apple_behaviour"],
+        )
+        .with_system_desc(
+            systems::SpeedCurveSystemDesc::default(),
+            "speed_curve",
+            &["apple_handler"],
+        )
+        .with(
+            systems::TimedEffectSystem::default(),
+            "timed_effects",
+            &["power_ups", "speed_curve"],
+        )
+        .with_system_desc(
+            systems::ScoreSystemDesc::default(),
+            "score",
+            &["apple_handler"],
+        )
+        .with_system_desc(
+            systems::StatsSystemDesc::default(),
+            "stats",
+            &["apple_handler"],
+        )
+        .with_system_desc(
+            systems::AchievementsSystemDesc::default(),
+            "achievements",
+            &["apple_handler"],
         )
-        .with_bundle(TransformBundle::new().with_dep(&["snake_render"]))?
-        .with_bundle(UiBundle::<StringBindings>::new())?;
-
-    let mut game = Application::new(resources, snake::SnakeGame, game_data)?;
-    game.run();
-
-    Ok(())
 }
@@ -0,0 +1,91 @@
+use amethyst::{
+    ecs::Entity,
+    prelude::*,
+    ui::{Anchor, FontHandle, UiText, UiTransform},
+};
+
+use crate::theme::{load_font, ThemeColors, Themes};
+
+pub const ROW_HEIGHT: f32 = 60.;
+/// Row height of the menus listing too many rows to fit with `ROW_HEIGHT`.
+pub const COMPACT_ROW_HEIGHT: f32 = 36.;
+pub const FIRST_ROW: f32 = -220.;
+
+/// Creates the texts of the menu screens with the font and colors of the selected theme.
+pub struct MenuText {
+    font: FontHandle,
+    colors: ThemeColors,
+}
+
+impl MenuText {
+    pub fn new(world: &World) -> Self {
+        MenuText {
+            font: load_font(world),
+            colors: world.read_resource::<Themes>().selected.colors.clone(),
+        }
+    }
+
+    pub fn create(&self, world: &mut World, id: &str, y: f32, text: String, size: f32) -> Entity {
+        let transform = UiTransform::new(
+            id.to_string(),
+            Anchor::TopMiddle,
+            Anchor::TopMiddle,
+            0.0,
+            y,
+            1.,
+            1000.,
+            size * 1.5,
+        );
+        world
+            .create_entity()
+            .with(transform)
+            .with(UiText::new(self.font.clone(), text, self.colors.text, size))
+            .build()
+    }
+
+    /// Creates one text per label, `row_height` apart, starting at `FIRST_ROW`.
+    pub fn create_rows(
+        &self,
+        world: &mut World,
+        id: &str,
+        labels: Vec<String>,
+        row_height: f32,
+    ) -> Vec<Entity> {
+        let size = row_height * 2. / 3.;
+        labels
+            .into_iter()
+            .enumerate()
+            .map(|(index, label)| {
+                let y = FIRST_ROW - index as f32 * row_height;
+                self.create(world, &format!("{}_{}", id, index), y, label, size)
+            })
+            .collect()
+    }
+
+    /// Rewrites the rows with `labels`, drawing the `selected` one with the highlight color.
+    pub fn update_rows(
+        &self,
+        world: &mut World,
+        rows: &[Entity],
+        labels: Vec<String>,
+        selected: usize,
+    ) {
+        let mut texts = world.write_storage::<UiText>();
+        for (index, (row, label)) in rows.iter().zip(labels).enumerate() {
+            if let Some(text) = texts.get_mut(*row) {
+                text.text = label;
+                text.color = if index == selected {
+                    self.colors.highlight
+                } else {
+                    self.colors.text
+                };
+            }
+        }
+    }
+
+    pub fn set_text(&self, world: &mut World, entity: Entity, text: String) {
+        if let Some(ui_text) = world.write_storage::<UiText>().get_mut(entity) {
+            ui_text.text = text;
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use amethyst::ecs::Entity;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Cell {
+    Empty,
+    Wall,
+    Snake { snake: Entity, segment: u64 },
+    Apple(Entity),
+    PowerUp(Entity),
+}
+
+impl Cell {
+    pub fn is_blocking(&self) -> bool {
+        match self {
+            Cell::Wall | Cell::Snake { .. } => true,
+            Cell::Empty | Cell::Apple(_) | Cell::PowerUp(_) => false,
+        }
+    }
+}
+
+pub struct OccupancyGrid {
+    width: i32,
+    height: i32,
+    cells: Vec<Cell>,
+}
+
+impl OccupancyGrid {
+    pub fn new(width: i32, height: i32) -> Self {
+        OccupancyGrid {
+            width,
+            height,
+            cells: vec![Cell::Empty; (width * height) as usize],
+        }
+    }
+
+    pub fn add_border_walls(&mut self) {
+        for x in 0..self.width {
+            self.set(&glm::vec2(x, 0), Cell::Wall);
+            self.set(&glm::vec2(x, self.height - 1), Cell::Wall);
+        }
+        for y in 0..self.height {
+            self.set(&glm::vec2(0, y), Cell::Wall);
+            self.set(&glm::vec2(self.width - 1, y), Cell::Wall);
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn contains(&self, position: &glm::IVec2) -> bool {
+        position.x >= 0 && position.x < self.width && position.y >= 0 && position.y < self.height
+    }
+
+    pub fn get(&self, position: &glm::IVec2) -> Cell {
+        if self.contains(position) {
+            self.cells[self.index(position)]
+        } else {
+            Cell::Wall
+        }
+    }
+
+    pub fn set(&mut self, position: &glm::IVec2, cell: Cell) {
+        if self.contains(position) {
+            let index = self.index(position);
+            self.cells[index] = cell;
+        }
+    }
+
+    pub fn clear(&mut self, position: &glm::IVec2) {
+        self.set(position, Cell::Empty);
+    }
+
+    pub fn is_free(&self, position: &glm::IVec2) -> bool {
+        self.get(position) == Cell::Empty
+    }
+
+    pub fn free_cells(&self) -> impl Iterator<Item = glm::IVec2> + '_ {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| **cell == Cell::Empty)
+            .map(move |(index, _)| glm::vec2(index as i32 % width, index as i32 / width))
+    }
+
+    /// Brings `position` back on the board, as if its opposite edges were joined.
+    pub fn wrap(&self, position: &glm::IVec2) -> glm::IVec2 {
+        glm::vec2(
+            position.x.rem_euclid(self.width),
+            position.y.rem_euclid(self.height),
+        )
+    }
+
+    /// The position that `position` wraps to and that is the closest to `near`, possibly off
+    /// the board. It is `position` itself when the two cells are next to each other on the board.
+    pub fn unwrap_near(&self, position: &glm::IVec2, near: &glm::IVec2) -> glm::IVec2 {
+        let closest = |value: i32, near: i32, size: i32| {
+            near + (value - near + size / 2).rem_euclid(size) - size / 2
+        };
+        glm::vec2(
+            closest(position.x, near.x, self.width),
+            closest(position.y, near.y, self.height),
+        )
+    }
+
+    fn index(&self, position: &glm::IVec2) -> usize {
+        (position.y * self.width + position.x) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::time::Instant;
+
+    use amethyst::ecs::{Builder, World, WorldExt};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn outside_cells_are_walls() {
+        let grid = OccupancyGrid::new(4, 3);
+        assert_eq!(grid.get(&glm::vec2(-1, 0)), Cell::Wall);
+        assert_eq!(grid.get(&glm::vec2(4, 0)), Cell::Wall);
+        assert_eq!(grid.get(&glm::vec2(0, 3)), Cell::Wall);
+        assert!(grid.is_free(&glm::vec2(3, 2)));
+    }
+
+    #[test]
+    fn free_cells_skip_occupied_cells() {
+        let mut world = World::new();
+        let apple = world.create_entity().build();
+        let mut grid = OccupancyGrid::new(5, 4);
+        grid.add_border_walls();
+        grid.set(&glm::vec2(1, 1), Cell::Apple(apple));
+        grid.set(&glm::vec2(3, 2), Cell::Wall);
+
+        let free: Vec<glm::IVec2> = grid.free_cells().collect();
+        assert_eq!(
+            free,
+            vec![
+                glm::vec2(2, 1),
+                glm::vec2(3, 1),
+                glm::vec2(1, 2),
+                glm::vec2(2, 2)
+            ]
+        );
+
+        grid.clear(&glm::vec2(1, 1));
+        assert_eq!(grid.free_cells().next(), Some(glm::vec2(1, 1)));
+    }
+
+    #[test]
+    fn full_grid_has_no_free_cells() {
+        let mut grid = OccupancyGrid::new(3, 3);
+        grid.add_border_walls();
+        grid.set(&glm::vec2(1, 1), Cell::Wall);
+        assert_eq!(grid.free_cells().count(), 0);
+    }
+
+    #[test]
+    fn wrapped_cells_are_unwrapped_next_to_each_other() {
+        let grid = OccupancyGrid::new(10, 8);
+        assert_eq!(grid.wrap(&glm::vec2(-1, 8)), glm::vec2(9, 0));
+        assert_eq!(
+            grid.unwrap_near(&glm::vec2(9, 3), &glm::vec2(0, 3)),
+            glm::vec2(-1, 3)
+        );
+        assert_eq!(
+            grid.unwrap_near(&glm::vec2(4, 0), &glm::vec2(4, 7)),
+            glm::vec2(4, 8)
+        );
+        assert_eq!(
+            grid.unwrap_near(&glm::vec2(5, 3), &glm::vec2(4, 3)),
+            glm::vec2(5, 3)
+        );
+    }
+
+    /// A snake winding through every row of a `size` x `size` board, walls excluded.
+    fn long_snake(size: i32) -> Vec<glm::IVec2> {
+        (1..size - 1)
+            .flat_map(|y| {
+                let row: Vec<glm::IVec2> = (1..size - 1).map(|x| glm::vec2(x, y)).collect();
+                if y % 2 == 0 {
+                    row.into_iter().rev().collect::<Vec<_>>()
+                } else {
+                    row
+                }
+            })
+            .collect()
+    }
+
+    /// The cells reachable from `start`, the way a path finding AI explores the board.
+    fn reachable(start: glm::IVec2, is_free: impl Fn(&glm::IVec2) -> bool) -> usize {
+        let mut seen = vec![start];
+        let mut queue = VecDeque::from(vec![start]);
+        while let Some(position) = queue.pop_front() {
+            for offset in &[
+                glm::vec2(1, 0),
+                glm::vec2(-1, 0),
+                glm::vec2(0, 1),
+                glm::vec2(0, -1),
+            ] {
+                let next = position + offset;
+                if !seen.contains(&next) && is_free(&next) {
+                    seen.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        seen.len() - 1
+    }
+
+    /// Compares the grid with the nested scan of every snake part it replaced, on a snake of
+    /// several thousand segments. Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn benchmark_against_part_scans() {
+        const SIZE: i32 = 72;
+        let mut world = World::new();
+        let snake = world.create_entity().build();
+        let mut body = long_snake(SIZE);
+        // Leave a few free cells at the tail end for the apples and the AI.
+        body.truncate(body.len() - 40);
+        let head = body[0];
+
+        let mut grid = OccupancyGrid::new(SIZE, SIZE);
+        grid.add_border_walls();
+        for (index, position) in body.iter().enumerate() {
+            let segment = (body.len() - index) as u64;
+            grid.set(position, Cell::Snake { snake, segment });
+        }
+        let is_wall = |position: &glm::IVec2| {
+            position.x <= 0 || position.x >= SIZE - 1 || position.y <= 0 || position.y >= SIZE - 1
+        };
+        let scan_is_free =
+            |position: &glm::IVec2| !is_wall(position) && body.iter().all(|part| part != position);
+        let grid_is_free = |position: &glm::IVec2| grid.is_free(position);
+
+        let rounds = 200;
+        let started = Instant::now();
+        for _ in 0..rounds {
+            let collided = body[1..].iter().any(|part| *part == head) || is_wall(&head);
+            assert!(!collided);
+        }
+        let scan_collision = started.elapsed();
+        let started = Instant::now();
+        for _ in 0..rounds {
+            let collided = grid.get(&head)
+                != Cell::Snake {
+                    snake,
+                    segment: body.len() as u64,
+                };
+            assert!(!collided);
+        }
+        let grid_collision = started.elapsed();
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let started = Instant::now();
+        for _ in 0..rounds {
+            let apple = loop {
+                let position = glm::vec2(rng.gen_range(1, SIZE - 1), rng.gen_range(1, SIZE - 1));
+                if scan_is_free(&position) {
+                    break position;
+                }
+            };
+            assert!(grid.is_free(&apple));
+        }
+        let scan_apple = started.elapsed();
+        let started = Instant::now();
+        for _ in 0..rounds {
+            let free: Vec<glm::IVec2> = grid.free_cells().collect();
+            assert!(!free.is_empty());
+        }
+        let grid_apple = started.elapsed();
+
+        let tail_end = *body.last().unwrap();
+        let ai_rounds = 20;
+        let started = Instant::now();
+        for _ in 0..ai_rounds {
+            assert_eq!(reachable(tail_end, scan_is_free), 40);
+        }
+        let scan_ai = started.elapsed();
+        let started = Instant::now();
+        for _ in 0..ai_rounds {
+            assert_eq!(reachable(tail_end, grid_is_free), 40);
+        }
+        let grid_ai = started.elapsed();
+
+        println!("{} segments", body.len());
+        println!(
+            "collision: scan {:?}, grid {:?}",
+            scan_collision, grid_collision
+        );
+        println!("apple:     scan {:?}, grid {:?}", scan_apple, grid_apple);
+        println!("ai:        scan {:?}, grid {:?}", scan_ai, grid_ai);
+    }
+}
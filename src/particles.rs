@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+
+use amethyst::ecs::Entity;
+use serde::{Deserialize, Serialize};
+
+pub const APPLE_EATEN: &str = "apple_eaten";
+pub const DEATH: &str = "death";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EmitterPreset {
+    pub sprite: String,
+    pub count: usize,
+    pub lifetime: f32,
+    pub speed: (f32, f32),
+    #[serde(default)]
+    pub drag: f32,
+    pub scale: (f32, f32),
+    pub start_color: [f32; 4],
+    pub end_color: [f32; 4],
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ParticlePresets {
+    pub presets: HashMap<String, EmitterPreset>,
+}
+
+#[derive(Default)]
+pub struct ParticlePool {
+    pub free: Vec<Entity>,
+}
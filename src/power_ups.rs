@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// What a power-up does to the snake collecting it.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Effect {
+    /// Multiplies the time between two moves, so a factor under 1 speeds the game up.
+    SpeedBoost { interval_factor: f32 },
+    /// Same as `SpeedBoost`, with a factor over 1.
+    SlowMotion { interval_factor: f32 },
+    /// The snake goes through its own body.
+    Ghost,
+    /// Removes segments from the tail right away, keeping at least the head and the tail.
+    Shrink { segments: usize },
+    /// The apples move towards the head of the snake every tick.
+    Magnet,
+    /// Multiplies the points of the apples eaten by the snake.
+    ScoreMultiplier { factor: u32 },
+}
+
+impl Effect {
+    /// How much the effect multiplies the time between two moves.
+    pub fn interval_factor(&self) -> f32 {
+        match self {
+            Effect::SpeedBoost { interval_factor } | Effect::SlowMotion { interval_factor } => {
+                *interval_factor
+            }
+            _ => 1.0,
+        }
+    }
+
+    pub fn score_factor(&self) -> u32 {
+        match self {
+            Effect::ScoreMultiplier { factor } => *factor,
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PowerUp {
+    pub effect: Effect,
+    /// The name shown in the HUD while the effect lasts.
+    pub label: String,
+    pub sprite: String,
+    pub tint: [f32; 4],
+    pub duration_ticks: u64,
+    /// The chance of a power-up of this kind appearing on every tick.
+    pub probability: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PowerUpTable {
+    pub max_on_board: usize,
+    pub power_ups: Vec<PowerUp>,
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use amethyst::{config::Config, prelude::*};
+use log::warn;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::CommandLine;
+use crate::ruleset::Ruleset;
+use crate::settings::Settings;
+use crate::snake::{Direction, GameRng};
+
+/// A direction given to the snake of `player` before its move number `tick`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Turn {
+    pub tick: u64,
+    pub player: usize,
+    pub direction: Direction,
+}
+
+/// Everything needed to play a round again: the settings and rules it was played with, the seed
+/// of the apple positions and the directions given by the players.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub settings: Settings,
+    #[serde(default)]
+    pub ruleset: Ruleset,
+    pub turns: Vec<Turn>,
+}
+
+/// The replay being recorded or played during the current round.
+pub struct ReplaySession {
+    pub replay: Replay,
+    /// Where the round is saved when recording, `None` when playing it back.
+    pub record_to: Option<PathBuf>,
+    pub next_turn: usize,
+    pub directions: HashMap<usize, Direction>,
+}
+
+/// Seeds the `GameRng` of a new round and starts recording or playing it back when asked to on
+/// the command line.
+pub fn start_round(world: &mut World) {
+    let (seed, session) = match world.try_fetch::<CommandLine>() {
+        Some(command_line) => {
+            let seed = command_line
+                .replay
+                .as_ref()
+                .map(|replay| replay.seed)
+                .or(command_line.seed)
+                .unwrap_or_else(rand::random);
+            let session = match (&command_line.replay, &command_line.record) {
+                (Some(replay), _) => Some((replay.clone(), None)),
+                (None, Some(path)) => Some((
+                    Replay {
+                        seed,
+                        settings: world.read_resource::<Settings>().clone(),
+                        ruleset: world.read_resource::<Ruleset>().clone(),
+                        turns: vec![],
+                    },
+                    Some(path.clone()),
+                )),
+                (None, None) => None,
+            };
+            (seed, session)
+        }
+        None => (rand::random(), None),
+    };
+
+    world.insert(GameRng(StdRng::seed_from_u64(seed)));
+    match session {
+        Some((replay, record_to)) => {
+            // The round is played under the rules of the replay, whatever the settings name.
+            if record_to.is_none() {
+                world.insert(replay.ruleset.clone());
+            }
+            world.insert(ReplaySession {
+                replay,
+                record_to,
+                next_turn: 0,
+                directions: HashMap::new(),
+            });
+        }
+        None => {
+            world.remove::<ReplaySession>();
+        }
+    }
+}
+
+/// Writes the round that just ended when it was recorded.
+pub fn end_round(world: &World) {
+    if let Some(session) = world.try_fetch::<ReplaySession>() {
+        if let Some(path) = &session.record_to {
+            if let Err(error) = session.replay.write(path) {
+                warn!("Failed to save the replay to {}: {}", path.display(), error);
+            }
+        }
+    }
+}
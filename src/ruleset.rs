@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use amethyst::config::{Config, ConfigError};
+use rand::{rngs::StdRng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::fruits::Fruit;
+use crate::occupancy_grid::OccupancyGrid;
+use crate::settings::Settings;
+use crate::snake::Direction;
+
+/// What lies on the edges of the board.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Borders {
+    Walls,
+    /// A snake leaving the board comes back on the opposite side.
+    Wrap,
+}
+
+/// What happens to a snake running into its own body.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SelfCollision {
+    Death,
+    PassThrough,
+    /// The body is cut where the head bit it, and the snake goes on.
+    CutTail,
+}
+
+/// How fast the game starts, and how it speeds up as the apples are eaten.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SpeedCurve {
+    /// The time between two moves when a round starts, unless the `Settings` override it.
+    pub initial_ms: u64,
+    /// Taken off the time between two moves for every apple eaten.
+    pub step_ms: u64,
+    /// The time between two moves the curve stops at.
+    pub min_interval_ms: u64,
+}
+
+/// The rules a round is played under, loaded from `assets/rulesets`. A replay keeps the ruleset
+/// it was recorded with.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Ruleset {
+    pub borders: Borders,
+    pub self_collision: SelfCollision,
+    /// Replaces the growth of the fruits, by fruit name.
+    #[serde(default)]
+    pub fruit_growth: HashMap<String, i32>,
+    #[serde(default)]
+    pub speed: SpeedCurve,
+    /// The number of apples moving elsewhere when eaten.
+    pub apples: usize,
+    /// The Manhattan distance kept between a new apple and the heads of the snakes.
+    pub min_head_distance: i32,
+    /// How many times a snake can die before the game is over.
+    pub lives: u32,
+}
+
+impl Default for SpeedCurve {
+    fn default() -> Self {
+        SpeedCurve {
+            initial_ms: 200,
+            step_ms: 0,
+            min_interval_ms: 0,
+        }
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Ruleset {
+            borders: Borders::Walls,
+            self_collision: SelfCollision::Death,
+            fruit_growth: HashMap::new(),
+            speed: SpeedCurve::default(),
+            apples: 1,
+            min_head_distance: 3,
+            lives: 1,
+        }
+    }
+}
+
+impl Ruleset {
+    /// Loads the ruleset named in the `Settings`, or the classic rules when there is none. A
+    /// name ending with `.ron` is the path of a ruleset file rather than one of
+    /// `assets/rulesets`.
+    pub fn load_selected(assets: &Path, settings: &Settings) -> Result<Ruleset, ConfigError> {
+        match &settings.ruleset {
+            Some(path) if path.ends_with(".ron") => Ruleset::load(path),
+            Some(name) => Ruleset::load(assets.join("rulesets").join(format!("{}.ron", name))),
+            None => Ok(Ruleset::default()),
+        }
+    }
+
+    /// The cell a snake with its head on `position` moves to when heading to `direction`.
+    pub fn next_cell(
+        &self,
+        grid: &OccupancyGrid,
+        position: glm::IVec2,
+        direction: &Direction,
+    ) -> glm::IVec2 {
+        let next = position + direction.offset();
+        match self.borders {
+            Borders::Walls => next,
+            Borders::Wrap => grid.wrap(&next),
+        }
+    }
+
+    /// The segments a snake gains when eating `fruit`, or loses when negative.
+    pub fn growth(&self, fruit: &Fruit) -> i32 {
+        self.fruit_growth
+            .get(&fruit.name)
+            .copied()
+            .unwrap_or(fruit.growth)
+    }
+
+    /// A free cell for a new apple, away from the `heads` of the snakes when possible.
+    pub fn pick_apple_cell(
+        &self,
+        grid: &OccupancyGrid,
+        heads: &[glm::IVec2],
+        rng: &mut StdRng,
+    ) -> Option<glm::IVec2> {
+        let free_cells: Vec<glm::IVec2> = grid.free_cells().collect();
+
+        let preferred_cells: Vec<glm::IVec2> = free_cells
+            .iter()
+            .filter(|cell| {
+                heads.iter().all(|head| {
+                    (cell.x - head.x).abs() + (cell.y - head.y).abs() >= self.min_head_distance
+                })
+            })
+            .cloned()
+            .collect();
+
+        // Close to the end of the game there might be no cell far enough from the head, in which
+        // case any free cell will do.
+        preferred_cells
+            .choose(rng)
+            .or_else(|| free_cells.choose(rng))
+            .cloned()
+    }
+}
@@ -0,0 +1,169 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use amethyst::config::{Config, ConfigError};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+/// The smallest board width or height, walls included, leaving room for the starting snakes.
+pub const MIN_ARENA_SIZE: i32 = 10;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MovementRendering {
+    Snap,
+    Interpolated,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum CameraMode {
+    FitToBoard,
+    FollowHead {
+        visible_rows: f32,
+        dead_zone: (f32, f32),
+        smoothing: f32,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ScreenShake {
+    pub intensity: f32,
+    pub duration: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Volume {
+    pub master: f32,
+    pub music: f32,
+    pub effects: f32,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ControlScheme {
+    Wasd,
+    Arrows,
+    Gamepad(u32),
+    /// Holding the left mouse button steers towards the cursor.
+    Mouse,
+    /// Dragging with the left mouse button steers in the direction of the drag.
+    Swipe,
+}
+
+impl Default for ControlScheme {
+    fn default() -> Self {
+        ControlScheme::Wasd
+    }
+}
+
+impl ControlScheme {
+    /// The name of `action` in the input bindings for this scheme, e.g. `wasd_up`. The mouse
+    /// schemes have no actions.
+    pub fn action(self, action: &str) -> Option<String> {
+        match self {
+            ControlScheme::Wasd => Some(format!("wasd_{}", action)),
+            ControlScheme::Arrows => Some(format!("arrows_{}", action)),
+            ControlScheme::Gamepad(controller) => Some(format!("gamepad{}_{}", controller, action)),
+            ControlScheme::Mouse | ControlScheme::Swipe => None,
+        }
+    }
+
+    /// The names of the horizontal and vertical stick axes in the input bindings, if any.
+    pub fn stick_axes(self) -> Option<(String, String)> {
+        match self {
+            ControlScheme::Gamepad(controller) => Some((
+                format!("gamepad{}_x", controller),
+                format!("gamepad{}_y", controller),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the direction actions of a player point the snake in that direction, or turn it.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Steering {
+    Absolute,
+    /// The `left` and `right` actions turn the snake a quarter turn, the others are unused.
+    Relative,
+}
+
+impl Default for Steering {
+    fn default() -> Self {
+        Steering::Absolute
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PlayerSettings {
+    #[serde(default)]
+    pub skin: Option<String>,
+    #[serde(default)]
+    pub controls: ControlScheme,
+    #[serde(default)]
+    pub steering: Steering,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Settings {
+    /// Overrides the initial time between two moves of the `Ruleset`.
+    pub tick_interval_ms: Option<u64>,
+    pub stick_dead_zone: f32,
+    pub swipe_distance: f32,
+    pub movement_rendering: MovementRendering,
+    pub arena_width: i32,
+    pub arena_height: i32,
+    pub camera: CameraMode,
+    pub screen_shake: Option<ScreenShake>,
+    pub fullscreen: bool,
+    pub theme: String,
+    pub level: Option<String>,
+    pub ruleset: Option<String>,
+    pub players: Vec<PlayerSettings>,
+    pub volume: Volume,
+    pub hot_reload: bool,
+}
+
+impl Settings {
+    pub fn load_with_overrides(files: &UserFiles) -> Result<Settings, ConfigError> {
+        load_user_file(&files.settings, &files.assets.join("settings.ron"))
+    }
+
+    pub fn save(&self, files: &UserFiles) -> Result<(), ConfigError> {
+        save_user_file(self, &files.settings)
+    }
+}
+
+/// Loads the user file if there is one, falling back to the shipped defaults when it is missing
+/// or cannot be read.
+pub fn load_user_file<T: Config>(user: &Path, defaults: &Path) -> Result<T, ConfigError> {
+    if user.exists() {
+        match T::load(user) {
+            Ok(value) => return Ok(value),
+            Err(error) => warn!("Ignoring user file {}: {}", user.display(), error),
+        }
+    }
+    T::load(defaults)
+}
+
+pub fn save_user_file<T: Config>(value: &T, user: &Path) -> Result<(), ConfigError> {
+    if let Some(directory) = user.parent() {
+        fs::create_dir_all(directory).map_err(ConfigError::File)?;
+    }
+    value.write(user)
+}
+
+#[derive(Clone)]
+pub struct UserFiles {
+    pub assets: PathBuf,
+    pub settings: PathBuf,
+    pub bindings: PathBuf,
+}
+
+impl UserFiles {
+    pub fn new(app_root: &Path) -> Self {
+        UserFiles {
+            assets: app_root.join("assets"),
+            settings: app_root.join("user").join("settings.ron"),
+            bindings: app_root.join("user").join("bindings.ron"),
+        }
+    }
+}
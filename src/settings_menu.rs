@@ -0,0 +1,331 @@
+use std::fs;
+
+use amethyst::{
+    ecs::Entity,
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+};
+use log::warn;
+
+use crate::audio::{play_effect, Music, MusicMode, MENU};
+use crate::menu::{MenuText, FIRST_ROW, ROW_HEIGHT};
+use crate::ruleset::Ruleset;
+use crate::settings::{ControlScheme, Settings, Steering, UserFiles, MIN_ARENA_SIZE};
+use crate::snake::{apply_fullscreen, GameState, SnakeGame};
+use crate::theme::reload_themes;
+
+const MIN_TICK_INTERVAL_MS: u64 = 50;
+const MAX_TICK_INTERVAL_MS: u64 = 500;
+const MAX_ARENA_SIZE: i32 = 100;
+
+#[derive(Clone, Copy, PartialEq)]
+enum SettingsOption {
+    Speed,
+    ArenaWidth,
+    ArenaHeight,
+    Theme,
+    MasterVolume,
+    MusicVolume,
+    EffectsVolume,
+    Fullscreen,
+    Controls,
+    Steering,
+}
+
+const OPTIONS: [SettingsOption; 10] = [
+    SettingsOption::Speed,
+    SettingsOption::ArenaWidth,
+    SettingsOption::ArenaHeight,
+    SettingsOption::Theme,
+    SettingsOption::MasterVolume,
+    SettingsOption::MusicVolume,
+    SettingsOption::EffectsVolume,
+    SettingsOption::Fullscreen,
+    SettingsOption::Controls,
+    SettingsOption::Steering,
+];
+
+/// Edits a copy of the `Settings`, which replaces the current ones when confirmed with Enter.
+/// Only the options changed in the menu are then written to the user settings file, so that the
+/// command line overrides are not saved. Escape leaves without saving.
+pub struct SettingsMenuState {
+    settings: Settings,
+    edited: Vec<SettingsOption>,
+    /// The time between two moves of the `Ruleset`, used when the settings do not override it.
+    ruleset_tick_interval_ms: u64,
+    themes: Vec<String>,
+    selected: usize,
+    rows: Vec<Entity>,
+    text: Option<MenuText>,
+}
+
+impl SettingsMenuState {
+    pub fn new(world: &World) -> Self {
+        let settings = world.read_resource::<Settings>().clone();
+        let mut themes: Vec<String> =
+            fs::read_dir(world.read_resource::<UserFiles>().assets.join("themes"))
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok())
+                        .filter(|entry| entry.path().is_dir())
+                        .filter_map(|entry| entry.file_name().into_string().ok())
+                        .collect()
+                })
+                .unwrap_or_default();
+        if !themes.contains(&settings.theme) {
+            themes.push(settings.theme.clone());
+        }
+        themes.sort();
+
+        SettingsMenuState {
+            settings,
+            edited: vec![],
+            ruleset_tick_interval_ms: world.read_resource::<Ruleset>().speed.initial_ms,
+            themes,
+            selected: 0,
+            rows: vec![],
+            text: None,
+        }
+    }
+
+    fn label(&self, option: SettingsOption) -> String {
+        let settings = &self.settings;
+        let percent = |volume: f32| (volume * 100.0).round();
+        match option {
+            SettingsOption::Speed => match settings.tick_interval_ms {
+                Some(interval) => format!("Speed: {} ms per move", interval),
+                None => format!(
+                    "Speed: {} ms per move (ruleset)",
+                    self.ruleset_tick_interval_ms
+                ),
+            },
+            SettingsOption::ArenaWidth => format!("Arena width: {}", settings.arena_width),
+            SettingsOption::ArenaHeight => format!("Arena height: {}", settings.arena_height),
+            SettingsOption::Theme => format!("Theme: {}", settings.theme),
+            SettingsOption::MasterVolume => {
+                format!("Master volume: {}%", percent(settings.volume.master))
+            }
+            SettingsOption::MusicVolume => {
+                format!("Music volume: {}%", percent(settings.volume.music))
+            }
+            SettingsOption::EffectsVolume => {
+                format!("Effects volume: {}%", percent(settings.volume.effects))
+            }
+            SettingsOption::Fullscreen => format!(
+                "Fullscreen: {}",
+                if settings.fullscreen { "on" } else { "off" }
+            ),
+            SettingsOption::Controls => format!(
+                "Controls: {:?}",
+                settings
+                    .players
+                    .first()
+                    .map_or(ControlScheme::default(), |player| player.controls)
+            ),
+            SettingsOption::Steering => format!(
+                "Steering: {:?}",
+                settings
+                    .players
+                    .first()
+                    .map_or(Steering::default(), |player| player.steering)
+            ),
+        }
+    }
+
+    fn change(&mut self, option: SettingsOption, step: i32) {
+        if !self.edited.contains(&option) {
+            self.edited.push(option);
+        }
+        let settings = &mut self.settings;
+        let volume_step = |volume: &mut f32| {
+            *volume = (*volume + step as f32 * 0.1).max(0.0).min(1.0);
+        };
+        match option {
+            SettingsOption::Speed => {
+                let ruleset = self.ruleset_tick_interval_ms as i64;
+                let current = settings
+                    .tick_interval_ms
+                    .map_or(ruleset, |interval| interval as i64);
+                let interval = (current + step as i64 * 10)
+                    .max(MIN_TICK_INTERVAL_MS as i64)
+                    .min(MAX_TICK_INTERVAL_MS as i64);
+                // Reaching or stepping over the interval of the ruleset goes back to following it.
+                let back_to_ruleset = current != ruleset
+                    && (interval - ruleset).signum() != (current - ruleset).signum();
+                settings.tick_interval_ms = if back_to_ruleset {
+                    None
+                } else {
+                    Some(interval as u64)
+                };
+            }
+            SettingsOption::ArenaWidth => {
+                settings.arena_width = (settings.arena_width + step * 2)
+                    .max(MIN_ARENA_SIZE)
+                    .min(MAX_ARENA_SIZE);
+            }
+            SettingsOption::ArenaHeight => {
+                settings.arena_height = (settings.arena_height + step * 2)
+                    .max(MIN_ARENA_SIZE)
+                    .min(MAX_ARENA_SIZE);
+            }
+            SettingsOption::Theme => {
+                let current = self
+                    .themes
+                    .iter()
+                    .position(|theme| *theme == settings.theme)
+                    .unwrap_or(0) as i32;
+                let count = self.themes.len() as i32;
+                settings.theme = self.themes[(current + step).rem_euclid(count) as usize].clone();
+            }
+            SettingsOption::MasterVolume => volume_step(&mut settings.volume.master),
+            SettingsOption::MusicVolume => volume_step(&mut settings.volume.music),
+            SettingsOption::EffectsVolume => volume_step(&mut settings.volume.effects),
+            SettingsOption::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            SettingsOption::Controls => {
+                if let Some(player) = settings.players.first_mut() {
+                    player.controls = match player.controls {
+                        ControlScheme::Wasd => ControlScheme::Arrows,
+                        ControlScheme::Arrows => ControlScheme::Gamepad(0),
+                        ControlScheme::Gamepad(0) => ControlScheme::Gamepad(1),
+                        ControlScheme::Gamepad(_) => ControlScheme::Mouse,
+                        ControlScheme::Mouse => ControlScheme::Swipe,
+                        ControlScheme::Swipe => ControlScheme::Wasd,
+                    };
+                }
+            }
+            SettingsOption::Steering => {
+                if let Some(player) = settings.players.first_mut() {
+                    player.steering = match player.steering {
+                        Steering::Absolute => Steering::Relative,
+                        Steering::Relative => Steering::Absolute,
+                    };
+                }
+            }
+        }
+    }
+
+    fn labels(&self) -> Vec<String> {
+        OPTIONS.iter().map(|option| self.label(*option)).collect()
+    }
+
+    fn refresh(&self, world: &mut World) {
+        if let Some(text) = &self.text {
+            text.update_rows(world, &self.rows, self.labels(), self.selected);
+        }
+    }
+
+    fn apply(&self, world: &mut World) {
+        let theme_changed = {
+            let settings = world.read_resource::<Settings>();
+            settings.theme != self.settings.theme
+        };
+
+        {
+            let files = world.read_resource::<UserFiles>();
+            let saved = Settings::load_with_overrides(&files).and_then(|mut saved| {
+                for option in &self.edited {
+                    copy_option(*option, &self.settings, &mut saved);
+                }
+                saved.save(&files)
+            });
+            if let Err(error) = saved {
+                warn!(
+                    "Failed to save the settings to {}: {}",
+                    files.settings.display(),
+                    error
+                );
+            }
+        }
+
+        world.insert(self.settings.clone());
+        if theme_changed {
+            if let Err(error) = reload_themes(world) {
+                warn!("Failed to load theme {}: {}", self.settings.theme, error);
+            }
+        }
+        apply_fullscreen(world, self.settings.fullscreen);
+    }
+}
+
+/// Copies the value of `option` from `from` to `to`.
+fn copy_option(option: SettingsOption, from: &Settings, to: &mut Settings) {
+    match option {
+        SettingsOption::Speed => to.tick_interval_ms = from.tick_interval_ms,
+        SettingsOption::ArenaWidth => to.arena_width = from.arena_width,
+        SettingsOption::ArenaHeight => to.arena_height = from.arena_height,
+        SettingsOption::Theme => to.theme = from.theme.clone(),
+        SettingsOption::MasterVolume => to.volume.master = from.volume.master,
+        SettingsOption::MusicVolume => to.volume.music = from.volume.music,
+        SettingsOption::EffectsVolume => to.volume.effects = from.volume.effects,
+        SettingsOption::Fullscreen => to.fullscreen = from.fullscreen,
+        SettingsOption::Controls | SettingsOption::Steering => {
+            if let (Some(from), Some(to)) = (from.players.first(), to.players.first_mut()) {
+                if option == SettingsOption::Controls {
+                    to.controls = from.controls;
+                } else {
+                    to.steering = from.steering;
+                }
+            }
+        }
+    }
+}
+
+impl SimpleState for SettingsMenuState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.delete_all();
+        world.insert(GameState::InMenu);
+        world.write_resource::<Music>().set_mode(MusicMode::Menu);
+
+        let text = MenuText::new(world);
+        text.create(world, "settings", -50., "Settings".to_string(), 100.);
+        self.rows = text.create_rows(world, "settings_option", self.labels(), ROW_HEIGHT);
+        text.create(
+            world,
+            "settings_hint",
+            FIRST_ROW - (OPTIONS.len() as f32 + 0.5) * ROW_HEIGHT,
+            "Up/Down: choose   Left/Right: change   Enter: save   Escape: cancel".to_string(),
+            25.,
+        );
+        self.text = Some(text);
+
+        self.refresh(world);
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        let world = data.world;
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(&event) {
+                return Trans::Quit;
+            }
+            if is_key_down(&event, VirtualKeyCode::Escape) {
+                return Trans::Replace(Box::new(SnakeGame));
+            }
+            if is_key_down(&event, VirtualKeyCode::Return) {
+                self.apply(world);
+                return Trans::Replace(Box::new(SnakeGame));
+            }
+
+            let count = OPTIONS.len();
+            if is_key_down(&event, VirtualKeyCode::Up) {
+                self.selected = (self.selected + count - 1) % count;
+            } else if is_key_down(&event, VirtualKeyCode::Down) {
+                self.selected = (self.selected + 1) % count;
+            } else if is_key_down(&event, VirtualKeyCode::Left) {
+                self.change(OPTIONS[self.selected], -1);
+            } else if is_key_down(&event, VirtualKeyCode::Right) {
+                self.change(OPTIONS[self.selected], 1);
+            } else {
+                return Trans::None;
+            }
+            play_effect(world, MENU);
+            self.refresh(world);
+        }
+
+        Trans::None
+    }
+}
@@ -1,24 +1,44 @@
 use amethyst::{
-    assets::{AssetStorage, Loader},
-    core::{timing::Stopwatch, transform::Transform},
-    input::{get_key, is_close_requested, is_key_down, VirtualKeyCode},
+    assets::{AssetStorage, Handle, Loader},
+    core::{math::Vector2, timing::Stopwatch, transform::Transform},
+    ecs::{Entity, Join},
+    input::{get_key, is_close_requested, is_key_down, InputEvent, VirtualKeyCode},
     prelude::*,
-    renderer::{Camera, ImageFormat, SpriteRender, SpriteSheet, SpriteSheetFormat, Texture},
+    renderer::{Camera, ImageFormat, SpriteRender, SpriteSheet, Texture},
+    ui::UiText,
     window::ScreenDimensions,
+    winit::Window,
 };
 use log::info;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
 
+use crate::animation::APPLE_IDLE;
+use crate::audio::{init_audio, Music, MusicMode, Sounds};
+use crate::bindings_menu::BindingsMenuState;
+use crate::cli::CommandLine;
 use crate::components::{
-    AppleComponent, GamePositionComponent, SnakeHeadComponent, SnakePartComponent,
+    AppleComponent, CameraRigComponent, GamePositionComponent, SnakeComponent,
+    SpriteAnimationComponent,
 };
+use crate::fruits::{AppleBehaviour, FruitTable};
 use crate::game_over::GameOverState;
+use crate::hot_reload;
+use crate::level::Level;
+use crate::menu::MenuText;
+use crate::occupancy_grid::{Cell, OccupancyGrid};
+use crate::particles::ParticlePool;
+use crate::replay;
+use crate::ruleset::{Borders, Ruleset};
+use crate::settings::Settings;
+use crate::settings_menu::SettingsMenuState;
 use crate::snake::GameState::GameOver;
+use crate::stats::Stats;
+use crate::theme::{Theme, Themes};
+use crate::victory::VictoryState;
+use std::collections::HashMap;
 use std::ops::Deref;
-
-pub const ARENA_WIDTH: i32 = 52;
-pub const ARENA_HEIGHT: i32 = 32;
-pub const ARENA_PLAYABLE_WIDTH: i32 = 50;
-pub const ARENA_PLAYABLE_HEIGHT: i32 = 30;
+use std::time::Duration;
 
 pub struct SnakeGame;
 
@@ -28,27 +48,79 @@ impl SimpleState for SnakeGame {
 
         world.delete_all();
 
-        let dimensions = (*world.read_resource::<ScreenDimensions>()).clone();
+        let headless = world.read_resource::<CommandLine>().headless();
+        if !headless {
+            if !world.has_value::<Sounds>() {
+                init_audio(world);
+            }
+            world.write_resource::<Music>().set_mode(MusicMode::Game);
+        }
 
-        let sprites = load_sprites(world);
+        replay::start_round(world);
 
-        let snake_sprites = SnakeSprites {
-            sprite_renders: sprites,
+        let snake_sprites = load_sprites(world);
+        let tile_size = TileSize {
+            size: world
+                .read_resource::<Themes>()
+                .selected
+                .sprite_sheet
+                .tile_size(),
         };
-        let mut next_direction = NextDirection {
-            direction: Direction::Right,
-            time_since_last_action: Stopwatch::new(),
+
+        let interval = Duration::from_millis(
+            world
+                .read_resource::<Settings>()
+                .tick_interval_ms
+                .unwrap_or_else(|| world.read_resource::<Ruleset>().speed.initial_ms),
+        );
+        let mut tick_clock = TickClock {
+            base_interval: interval,
+            interval,
+            since_last_tick: Stopwatch::new(),
+            ticks: 0,
         };
-        next_direction.time_since_last_action.start();
+        tick_clock.since_last_tick.start();
 
         world.insert(snake_sprites);
-        world.insert(next_direction);
+        world.insert(tile_size);
+        world.insert(tick_clock);
+        world.insert(ParticlePool::default());
+        world.insert(DeathTime {
+            time: Stopwatch::new(),
+        });
         world.insert(GameState::Playing);
+        let (arena_width, arena_height) = {
+            let settings = world.read_resource::<Settings>();
+            (settings.arena_width, settings.arena_height)
+        };
+        let mut grid = OccupancyGrid::new(arena_width, arena_height);
+        if world.read_resource::<Ruleset>().borders == Borders::Walls {
+            grid.add_border_walls();
+        }
+        world.read_resource::<Level>().place_walls(&mut grid);
+        world.insert(grid);
+
+        if headless {
+            init_snakes(world);
+            init_apples(world);
+            return;
+        }
+
+        let dimensions = (*world.read_resource::<ScreenDimensions>()).clone();
+        let fullscreen = world.read_resource::<Settings>().fullscreen;
+        apply_fullscreen(world, fullscreen);
 
         init_camera(world, &dimensions);
         init_board(world);
-        init_snake(world);
-        init_apple(world);
+        init_snakes(world);
+        init_apples(world);
+        init_pause_label(world);
+        init_hud(world);
+        hot_reload::init_error_label(world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        replay::end_round(data.world);
     }
 
     fn handle_event(
@@ -56,11 +128,34 @@ impl SimpleState for SnakeGame {
         mut _data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
+        if let StateEvent::Input(InputEvent::ActionPressed(action)) = &event {
+            if action == PAUSE_ACTION {
+                toggle_pause(_data.world);
+            }
+        }
+
         if let StateEvent::Window(event) = &event {
             if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
                 return Trans::Quit;
             }
 
+            if is_key_down(&event, VirtualKeyCode::F1) {
+                return Trans::Replace(Box::new(SettingsMenuState::new(_data.world)));
+            }
+
+            if is_key_down(&event, VirtualKeyCode::F2) {
+                return Trans::Replace(Box::new(BindingsMenuState::default()));
+            }
+
+            if is_key_down(&event, VirtualKeyCode::F11) {
+                let fullscreen = {
+                    let mut settings = _data.world.write_resource::<Settings>();
+                    settings.fullscreen = !settings.fullscreen;
+                    settings.fullscreen
+                };
+                apply_fullscreen(_data.world, fullscreen);
+            }
+
             if let Some(event) = get_key(&event) {
                 info!("handling key event: {:?}", event);
             }
@@ -70,60 +165,151 @@ impl SimpleState for SnakeGame {
     }
 
     fn update(&mut self, _data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if hot_reload::apply_changes(_data.world) {
+            return SimpleTrans::Replace(Box::new(SnakeGame));
+        }
+
         let state = _data.world.read_resource::<GameState>();
+        let ended = *state == GameState::GameOver || *state == GameState::Won;
+        if ended && _data.world.read_resource::<CommandLine>().headless() {
+            log_result(_data.world, &state);
+            return SimpleTrans::Quit;
+        }
 
         let mut trans = SimpleTrans::None;
         if *state == GameState::GameOver {
-            trans = SimpleTrans::Replace(Box::new(GameOverState))
+            let mut death_time = _data.world.write_resource::<DeathTime>();
+            if let Stopwatch::Waiting = death_time.time {
+                death_time.time.start();
+            }
+            if death_time.time.elapsed().as_millis() > 1000 {
+                trans = SimpleTrans::Replace(Box::new(GameOverState))
+            }
+        } else if *state == GameState::Won {
+            trans = SimpleTrans::Replace(Box::new(VictoryState))
         }
         trans
     }
 }
 
+/// Logs how the round ended, as a headless game has no game over or victory screen to show it.
+fn log_result(world: &World, state: &GameState) {
+    let result = if *state == GameState::Won {
+        "Victory"
+    } else {
+        "Game over"
+    };
+    info!("{}: {}", result, *world.read_resource::<Stats>());
+    let snakes = world.read_storage::<SnakeComponent>();
+    for snake in (&snakes).join() {
+        info!("Player {} scored {}", snake.player + 1, snake.score);
+    }
+}
+
 fn init_camera(world: &mut World, dimensions: &ScreenDimensions) {
+    let board_center = {
+        let grid = world.read_resource::<OccupancyGrid>();
+        let tile_size = world.read_resource::<TileSize>();
+        board_center(&grid, tile_size.size)
+    };
     let mut transform = Transform::default();
-    transform.set_translation_xyz(
-        dimensions.width() * 0.5 - 16.,
-        dimensions.height() * 0.5 - 16.,
-        1.,
-    );
+    transform.set_translation_xyz(board_center.x, board_center.y, 1.);
 
     world
         .create_entity()
         .with(Camera::standard_2d(dimensions.width(), dimensions.height()))
+        .with(CameraRigComponent::default())
         .with(transform)
         .build();
 }
 
-fn load_sprites(world: &mut World) -> Vec<SpriteRender> {
-    let texture_handle = {
-        let loader = world.read_resource::<Loader>();
-        let texture_storage = world.read_resource::<AssetStorage<Texture>>();
-        loader.load(
-            "sprites/snake.png",
-            ImageFormat::default(),
-            (),
-            &texture_storage,
-        )
+pub fn board_center(grid: &OccupancyGrid, tile_size: f32) -> Vector2<f32> {
+    Vector2::new(
+        (grid.width() - 1) as f32 * tile_size * 0.5,
+        (grid.height() - 1) as f32 * tile_size * 0.5,
+    )
+}
+
+pub fn board_size(grid: &OccupancyGrid, tile_size: f32) -> Vector2<f32> {
+    Vector2::new(
+        grid.width() as f32 * tile_size,
+        grid.height() as f32 * tile_size,
+    )
+}
+
+pub fn apply_fullscreen(world: &World, fullscreen: bool) {
+    let mut window_mode = world.write_resource::<WindowMode>();
+    if window_mode.fullscreen == fullscreen {
+        return;
+    }
+
+    if let Some(window) = world.try_fetch::<Window>() {
+        if fullscreen {
+            window.set_fullscreen(Some(window.get_current_monitor()));
+        } else {
+            window.set_fullscreen(None);
+        }
+    }
+    window_mode.fullscreen = fullscreen;
+}
+
+fn load_sprites(world: &World) -> SnakeSprites {
+    let themes = world.read_resource::<Themes>();
+
+    let mut sheet_sprites = HashMap::new();
+    let mut load_theme = |theme: &Theme| -> HashMap<String, SpriteRender> {
+        let sheet = load_sprite_sheet(world, theme);
+        let sprite_numbers = theme.sprite_sheet.sprite_numbers();
+        let sprite_renders = sprite_numbers
+            .iter()
+            .map(|(name, number)| {
+                let sprite_render = SpriteRender {
+                    sprite_sheet: sheet.clone(),
+                    sprite_number: *number,
+                };
+                (name.clone(), sprite_render)
+            })
+            .collect();
+        sheet_sprites.insert(sheet, sprite_numbers);
+        sprite_renders
     };
 
-    let sheet_handle = {
-        let loader = world.read_resource::<Loader>();
-        let sheet_storage = world.read_resource::<AssetStorage<SpriteSheet>>();
-        loader.load(
-            "sprites/snake.ron",
-            SpriteSheetFormat(texture_handle),
-            (),
-            &sheet_storage,
-        )
+    let default_sprites = load_theme(&themes.default);
+    let mut loaded = HashMap::new();
+    loaded.insert(themes.default.name.clone(), default_sprites.clone());
+    let mut theme_sprites = |theme: &Theme| {
+        loaded
+            .entry(theme.name.clone())
+            .or_insert_with(|| {
+                let mut sprite_renders = default_sprites.clone();
+                sprite_renders.extend(load_theme(theme));
+                sprite_renders
+            })
+            .clone()
     };
 
-    (0..7)
-        .map(|i| SpriteRender {
-            sprite_sheet: sheet_handle.clone(),
-            sprite_number: i,
-        })
-        .collect()
+    let sprite_renders = theme_sprites(&themes.selected);
+    let skins = themes.skins.iter().map(&mut theme_sprites).collect();
+    SnakeSprites {
+        sprite_renders,
+        skins,
+        sheet_sprites,
+    }
+}
+
+fn load_sprite_sheet(world: &World, theme: &Theme) -> Handle<SpriteSheet> {
+    let loader = world.read_resource::<Loader>();
+    let texture_handle = loader.load(
+        theme.texture.as_str(),
+        ImageFormat::default(),
+        (),
+        &world.read_resource::<AssetStorage<Texture>>(),
+    );
+    loader.load_from_data(
+        theme.sprite_sheet.build(texture_handle),
+        (),
+        &world.read_resource::<AssetStorage<SpriteSheet>>(),
+    )
 }
 
 fn read_sprite_renderer(world: &World, sprite_key: SnakeSpritesKeys) -> SpriteRender {
@@ -133,9 +319,14 @@ fn read_sprite_renderer(world: &World, sprite_key: SnakeSpritesKeys) -> SpriteRe
 }
 
 fn init_board(world: &mut World) {
-    for x in 0..ARENA_WIDTH {
-        for y in 0..ARENA_HEIGHT {
-            let sprite_key = if x == 0 || y == 0 || x == ARENA_WIDTH - 1 || y == ARENA_HEIGHT - 1 {
+    let (width, height) = {
+        let grid = world.read_resource::<OccupancyGrid>();
+        (grid.width(), grid.height())
+    };
+    for x in 0..width {
+        for y in 0..height {
+            let cell = world.read_resource::<OccupancyGrid>().get(&glm::vec2(x, y));
+            let sprite_key = if cell == Cell::Wall {
                 SnakeSpritesKeys::Wall
             } else {
                 SnakeSpritesKeys::Grass
@@ -157,74 +348,168 @@ fn init_board(world: &mut World) {
     }
 }
 
-fn init_snake(world: &mut World) {
-    let sprite_renderer_body = read_sprite_renderer(world, SnakeSpritesKeys::SnakeBody);
-    let sprite_renderer_head = read_sprite_renderer(world, SnakeSpritesKeys::SnakeHead);
+fn init_snakes(world: &mut World) {
+    let (width, height) = {
+        let grid = world.read_resource::<OccupancyGrid>();
+        (grid.width(), grid.height())
+    };
+    let players = world.read_resource::<Settings>().players.len().max(1);
 
-    let mut transform_head = Transform::default();
-    transform_head.set_translation_xyz((11 * 32) as f32, (10 * 32) as f32, 0.);
-    let mut transform_e1 = Transform::default();
-    transform_e1.set_translation_xyz((10 * 32) as f32, (10 * 32) as f32, 0.);
-    let mut transform_e2 = Transform::default();
-    transform_e2.set_translation_xyz((10 * 32) as f32, (9 * 32) as f32, 0.);
+    // The snakes start on the left of the board, spread evenly on its height and heading right.
+    let start_x = (width / 4).max(1);
+    for player in 0..players {
+        let y = (player as i32 + 1) * height / (players as i32 + 1);
+        init_snake(world, player, start_x, y);
+    }
+}
+
+fn init_snake(world: &mut World, player: usize, start_x: i32, y: i32) {
+    let sprite_renderer_body = world
+        .read_resource::<SnakeSprites>()
+        .get_skin_sprite_clone(player, SnakeSpritesKeys::SnakeBody);
+
+    let snake_entity = world.create_entity().build();
+    let lives = world.read_resource::<Ruleset>().lives;
+    let mut snake = SnakeComponent::new(player, Direction::Right, lives);
+    for x in start_x..=start_x + 2 {
+        let position = glm::vec2(x, y);
+        let segment = world
+            .create_entity()
+            .with(GamePositionComponent::new(position.x, position.y))
+            .with(sprite_renderer_body.clone())
+            .with({
+                let mut transform = Transform::default();
+                transform.set_translation_z(0.5);
+                transform
+            })
+            .build();
+        snake.push_head(position, segment);
+        world.write_resource::<OccupancyGrid>().set(
+            &position,
+            Cell::Snake {
+                snake: snake_entity,
+                segment: snake.head_serial,
+            },
+        );
+    }
 
-    let e2 = world
-        .create_entity()
-        .with(SnakePartComponent {
-            next_snake_part: None,
-        })
-        .with(GamePositionComponent::new(10, 10))
-        .with(sprite_renderer_body.clone())
-        .with({
-            let mut transform = Transform::default();
-            transform.set_translation_z(0.5);
-            transform
-        })
-        .build();
-    let e1 = world
-        .create_entity()
-        .with(SnakePartComponent {
-            next_snake_part: Some(e2),
-        })
-        .with(GamePositionComponent::new(11, 10))
-        .with(sprite_renderer_body)
-        .with({
-            let mut transform = Transform::default();
-            transform.set_translation_z(0.5);
-            transform
-        })
-        .build();
     world
-        .create_entity()
-        .with(SnakePartComponent {
-            next_snake_part: Some(e1),
-        })
-        .with(GamePositionComponent::new(12, 10))
-        .with(sprite_renderer_head)
-        .with({
-            let mut transform = Transform::default();
-            transform.set_translation_z(0.5);
-            transform
-        })
-        .with(SnakeHeadComponent {})
-        .build();
+        .write_storage::<SnakeComponent>()
+        .insert(snake_entity, snake)
+        .unwrap();
 }
 
-fn init_apple(world: &mut World) {
-    let apple_sprite = read_sprite_renderer(world, SnakeSpritesKeys::Apple);
+/// Places the apples of the level, which are gone once eaten, then the ones of the
+/// `Ruleset`, which move elsewhere when eaten.
+fn init_apples(world: &mut World) {
+    let level_apples: Vec<glm::IVec2> = world
+        .read_resource::<Level>()
+        .apples
+        .iter()
+        .map(|&(x, y)| glm::vec2(x, y))
+        .collect();
+    for position in level_apples {
+        let free = {
+            let grid = world.read_resource::<OccupancyGrid>();
+            grid.contains(&position) && grid.is_free(&position)
+        };
+        if free {
+            spawn_apple(world, position, false);
+        }
+    }
+
+    let heads: Vec<glm::IVec2> = world
+        .read_storage::<SnakeComponent>()
+        .join()
+        .map(|snake| snake.head())
+        .collect();
+    for _ in 0..world.read_resource::<Ruleset>().apples {
+        let position = world.read_resource::<Ruleset>().pick_apple_cell(
+            &world.read_resource::<OccupancyGrid>(),
+            &heads,
+            &mut world.write_resource::<GameRng>().0,
+        );
+        match position {
+            Some(position) => spawn_apple(world, position, true),
+            None => break,
+        }
+    }
+}
+
+fn spawn_apple(world: &mut World, position: glm::IVec2, respawn: bool) {
+    let (fruit, behaviour, sprite) = {
+        let fruit_table = world.read_resource::<FruitTable>();
+        let fruit = fruit_table
+            .pick(&mut world.write_resource::<GameRng>().0)
+            .unwrap_or(0);
+        match fruit_table.fruits.get(fruit) {
+            Some(entry) => (fruit, entry.behaviour.clone(), Some(entry.sprite.clone())),
+            None => (fruit, AppleBehaviour::Static, None),
+        }
+    };
+    let apple_sprite = sprite
+        .and_then(|sprite| {
+            world
+                .read_resource::<SnakeSprites>()
+                .get_sprite_by_name(&sprite)
+        })
+        .unwrap_or_else(|| read_sprite_renderer(world, SnakeSpritesKeys::Apple));
     let mut apple_transform = Transform::default();
-    apple_transform.set_translation_xyz(5.0, 5.0, 0.5);
-    world
+    apple_transform.set_translation_z(0.5);
+    let apple = world
         .create_entity()
-        .with(AppleComponent {})
+        .with(AppleComponent {
+            fruit,
+            spawned_at: 0,
+            respawn,
+            behaviour,
+        })
+        .with(SpriteAnimationComponent::new(APPLE_IDLE))
         .with(apple_transform)
         .with(apple_sprite)
-        .with(GamePositionComponent::new(5, 5))
+        .with(GamePositionComponent::new(position.x, position.y))
         .build();
+    world
+        .write_resource::<OccupancyGrid>()
+        .set(&position, Cell::Apple(apple));
+}
 
-    world.insert(AppleWasEaten { was_eaten: false });
+fn init_pause_label(world: &mut World) {
+    let entity = MenuText::new(world).create(world, "paused", -300., String::new(), 100.);
+    world.insert(PauseLabel { entity });
 }
 
+fn init_hud(world: &mut World) {
+    let entity = MenuText::new(world).create(world, "hud", -50., String::new(), 30.);
+    world.insert(Hud { entity });
+}
+
+fn toggle_pause(world: &mut World) {
+    let state = *world.read_resource::<GameState>();
+    let paused = match state {
+        GameState::Playing => true,
+        GameState::Paused => false,
+        _ => return,
+    };
+
+    if paused {
+        world.insert(GameState::Paused);
+    } else {
+        world.insert(GameState::Playing);
+        world.write_resource::<TickClock>().restart();
+    }
+
+    let label = world.read_resource::<PauseLabel>().entity;
+    if let Some(text) = world.write_storage::<UiText>().get_mut(label) {
+        text.text = if paused {
+            "Paused".to_string()
+        } else {
+            String::new()
+        };
+    }
+}
+
+#[derive(Clone, Copy)]
 pub enum SnakeSpritesKeys {
     SnakeHead,
     SnakeBody,
@@ -235,17 +520,67 @@ pub enum SnakeSpritesKeys {
     Grass,
 }
 
+impl SnakeSpritesKeys {
+    pub const ALL: [SnakeSpritesKeys; 7] = [
+        SnakeSpritesKeys::SnakeHead,
+        SnakeSpritesKeys::SnakeBody,
+        SnakeSpritesKeys::SnakeTail,
+        SnakeSpritesKeys::SnakeTurn,
+        SnakeSpritesKeys::Apple,
+        SnakeSpritesKeys::Wall,
+        SnakeSpritesKeys::Grass,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SnakeSpritesKeys::SnakeHead => "snake_head",
+            SnakeSpritesKeys::SnakeBody => "snake_body",
+            SnakeSpritesKeys::SnakeTail => "snake_tail",
+            SnakeSpritesKeys::SnakeTurn => "snake_turn",
+            SnakeSpritesKeys::Apple => "apple",
+            SnakeSpritesKeys::Wall => "wall",
+            SnakeSpritesKeys::Grass => "grass",
+        }
+    }
+}
+
 pub struct SnakeSprites {
-    sprite_renders: Vec<SpriteRender>,
+    sprite_renders: HashMap<String, SpriteRender>,
+    skins: Vec<HashMap<String, SpriteRender>>,
+    sheet_sprites: HashMap<Handle<SpriteSheet>, HashMap<String, usize>>,
 }
 
 impl SnakeSprites {
     pub fn get_sprite_clone(&self, snake_sprite: SnakeSpritesKeys) -> SpriteRender {
-        self.sprite_renders[snake_sprite as usize].clone()
+        self.sprite_renders[snake_sprite.name()].clone()
+    }
+
+    pub fn get_skin_sprite_clone(
+        &self,
+        player: usize,
+        snake_sprite: SnakeSpritesKeys,
+    ) -> SpriteRender {
+        match self.skins.get(player) {
+            Some(skin) => skin[snake_sprite.name()].clone(),
+            None => self.get_sprite_clone(snake_sprite),
+        }
+    }
+
+    pub fn get_sprite_by_name(&self, name: &str) -> Option<SpriteRender> {
+        self.sprite_renders.get(name).cloned()
+    }
+
+    /// The number of the sprite called `name` in `sprite_sheet`, used to swap the sprite of an
+    /// entity while keeping the sheet (and so the skin) it is drawn with.
+    pub fn sprite_number(&self, sprite_sheet: &Handle<SpriteSheet>, name: &str) -> Option<usize> {
+        self.sheet_sprites
+            .get(sprite_sheet)
+            .and_then(|sprite_numbers| sprite_numbers.get(name))
+            .copied()
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum Direction {
     Up,
     Down,
@@ -253,17 +588,124 @@ pub enum Direction {
     Left,
 }
 
-pub struct NextDirection {
-    pub direction: Direction,
-    pub time_since_last_action: Stopwatch,
+impl Direction {
+    pub fn offset(&self) -> glm::IVec2 {
+        match self {
+            Direction::Up => glm::vec2(0, 1),
+            Direction::Right => glm::vec2(1, 0),
+            Direction::Down => glm::vec2(0, -1),
+            Direction::Left => glm::vec2(-1, 0),
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Right => Direction::Left,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    /// The direction after a quarter turn counterclockwise.
+    pub fn turned_left(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    /// The direction after a quarter turn clockwise.
+    pub fn turned_right(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    /// The direction of the dominant axis of `offset`, with y pointing up, so that an offset
+    /// slightly off a diagonal does not flicker between two directions.
+    pub fn dominant(offset: Vector2<f32>) -> Option<Direction> {
+        if offset.x == 0.0 && offset.y == 0.0 {
+            None
+        } else if offset.x.abs() > offset.y.abs() {
+            Some(if offset.x > 0.0 {
+                Direction::Right
+            } else {
+                Direction::Left
+            })
+        } else {
+            Some(if offset.y > 0.0 {
+                Direction::Up
+            } else {
+                Direction::Down
+            })
+        }
+    }
 }
 
-pub struct AppleWasEaten {
-    pub was_eaten: bool,
+/// The random number generator of everything affecting the game, seeded when a round starts so
+/// that it can be replayed.
+pub struct GameRng(pub StdRng);
+
+pub struct TileSize {
+    pub size: f32,
 }
 
-#[derive(Eq, PartialEq)]
+#[derive(Default)]
+pub struct WindowMode {
+    pub fullscreen: bool,
+}
+
+pub struct TickClock {
+    /// The time between two moves before the effects apply, which the speed curve of the
+    /// `Ruleset` shortens.
+    pub base_interval: Duration,
+    pub interval: Duration,
+    pub since_last_tick: Stopwatch,
+    /// How many times the snakes moved since the round started.
+    pub ticks: u64,
+}
+
+impl TickClock {
+    pub fn is_due(&self) -> bool {
+        self.since_last_tick.elapsed() > self.interval
+    }
+
+    pub fn restart(&mut self) {
+        self.since_last_tick.restart();
+    }
+
+    pub fn alpha(&self) -> f32 {
+        let alpha = self.since_last_tick.elapsed().as_secs_f32() / self.interval.as_secs_f32();
+        alpha.min(1.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum GameState {
     Playing,
     GameOver,
+    Won,
+    Paused,
+    InMenu,
+}
+
+pub const PAUSE_ACTION: &str = "pause";
+
+struct PauseLabel {
+    entity: Entity,
+}
+
+/// The text showing the scores and the active effects.
+pub struct Hud {
+    pub entity: Entity,
+}
+
+struct DeathTime {
+    time: Stopwatch,
 }
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use amethyst::{
+    assets::Handle,
+    renderer::{sprite::Sprite, SpriteSheet, Texture},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SpritePosition {
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SpriteList {
+    pub texture_width: u32,
+    pub texture_height: u32,
+    pub sprites: Vec<SpritePosition>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum SpriteSheetDefinition {
+    List(SpriteList),
+}
+
+impl SpriteSheetDefinition {
+    pub fn tile_size(&self) -> f32 {
+        let SpriteSheetDefinition::List(list) = self;
+        list.sprites
+            .first()
+            .map_or(32.0, |sprite| sprite.width as f32)
+    }
+
+    pub fn sprite_numbers(&self) -> HashMap<String, usize> {
+        let SpriteSheetDefinition::List(list) = self;
+        list.sprites
+            .iter()
+            .enumerate()
+            .map(|(number, sprite)| (sprite.name.clone(), number))
+            .collect()
+    }
+
+    pub fn missing_sprites<'a>(&self, required: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let numbers = self.sprite_numbers();
+        let mut missing: Vec<String> = required
+            .into_iter()
+            .filter(|name| !numbers.contains_key(*name))
+            .map(str::to_string)
+            .collect();
+        missing.sort();
+        missing.dedup();
+        missing
+    }
+
+    pub fn build(&self, texture: Handle<Texture>) -> SpriteSheet {
+        let SpriteSheetDefinition::List(list) = self;
+        let sprites = list
+            .sprites
+            .iter()
+            .map(|sprite| {
+                Sprite::from_pixel_values(
+                    list.texture_width,
+                    list.texture_height,
+                    sprite.width,
+                    sprite.height,
+                    sprite.x,
+                    sprite.y,
+                    [0.0, 0.0],
+                    false,
+                    false,
+                )
+            })
+            .collect();
+
+        SpriteSheet { texture, sprites }
+    }
+}
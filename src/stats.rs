@@ -0,0 +1,29 @@
+use std::fmt;
+
+/// What the players did since the game was launched, counted from the `GameEvent`s by the
+/// `StatsSystem` and logged at the end of every round.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    pub apples_eaten: u32,
+    pub turns: u32,
+    pub power_ups: u32,
+    pub deaths: u32,
+    pub levels_completed: u32,
+    pub longest_snake: usize,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} apples eaten, {} turns, {} power-ups, {} deaths, {} levels completed, \
+             longest snake {}",
+            self.apples_eaten,
+            self.turns,
+            self.power_ups,
+            self.deaths,
+            self.levels_completed,
+            self.longest_snake
+        )
+    }
+}
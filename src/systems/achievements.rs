@@ -0,0 +1,44 @@
+use amethyst::{
+    derive::SystemDesc,
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+use log::info;
+
+use crate::achievements::{Achievement, Achievements, LONG_SNAKE_LENGTH};
+use crate::events::GameEvent;
+
+/// Unlocks the `Achievements` reached through the `GameEvent`s, logging each one when unlocked.
+#[derive(SystemDesc)]
+#[system_desc(name(AchievementsSystemDesc))]
+pub struct AchievementsSystem {
+    #[system_desc(event_channel_reader)]
+    reader_id: ReaderId<GameEvent>,
+}
+
+impl AchievementsSystem {
+    pub fn new(reader_id: ReaderId<GameEvent>) -> Self {
+        AchievementsSystem { reader_id }
+    }
+}
+
+impl<'s> System<'s> for AchievementsSystem {
+    type SystemData = (Read<'s, EventChannel<GameEvent>>, Write<'s, Achievements>);
+
+    fn run(&mut self, (events, mut achievements): Self::SystemData) {
+        for event in events.read(&mut self.reader_id) {
+            let achievement = match event {
+                GameEvent::AppleEaten { .. } => Achievement::FirstApple,
+                GameEvent::PowerUpCollected { .. } => Achievement::FirstPowerUp,
+                GameEvent::Grew { length, .. } if *length >= LONG_SNAKE_LENGTH => {
+                    Achievement::LongSnake
+                }
+                GameEvent::LevelCompleted => Achievement::LevelCompleted,
+                _ => continue,
+            };
+            if achievements.unlock(achievement) {
+                info!("Achievement unlocked: {}", achievement);
+            }
+        }
+    }
+}
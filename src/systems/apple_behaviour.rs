@@ -0,0 +1,92 @@
+use amethyst::ecs::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::animation::APPLE_EXPIRING;
+use crate::components::{AppleComponent, GamePositionComponent, SpriteAnimationComponent};
+use crate::fruits::AppleBehaviour;
+use crate::occupancy_grid::{Cell, OccupancyGrid};
+use crate::snake::{GameRng, GameState, TickClock};
+
+/// Plays the `AppleBehaviour` of every apple once per tick: the expiring apples fade out before
+/// they rot away, and the wandering ones move to a free neighbouring cell.
+#[derive(Default)]
+pub struct AppleBehaviourSystem {
+    last_tick: u64,
+}
+
+impl<'s> System<'s> for AppleBehaviourSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, AppleComponent>,
+        WriteStorage<'s, GamePositionComponent>,
+        WriteStorage<'s, SpriteAnimationComponent>,
+        WriteExpect<'s, OccupancyGrid>,
+        WriteExpect<'s, GameRng>,
+        ReadExpect<'s, TickClock>,
+        ReadExpect<'s, GameState>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            apples,
+            mut positions,
+            mut animations,
+            mut grid,
+            mut rng,
+            tick_clock,
+            game_state,
+        ): Self::SystemData,
+    ) {
+        if *game_state != GameState::Playing || tick_clock.ticks == self.last_tick {
+            return;
+        }
+        self.last_tick = tick_clock.ticks;
+
+        for (entity, apple, position) in (&entities, &apples, &mut positions).join() {
+            // The apples eaten on this tick are now under a snake.
+            if grid.get(&position.position) != Cell::Apple(entity) {
+                continue;
+            }
+
+            let age = tick_clock.ticks - apple.spawned_at;
+            match apple.behaviour {
+                AppleBehaviour::Static => {}
+                AppleBehaviour::Expiring {
+                    lifetime_ticks,
+                    warning_ticks,
+                } => {
+                    let warned = animations
+                        .get(entity)
+                        .map_or(false, |animation| animation.is_playing(APPLE_EXPIRING));
+                    if age + warning_ticks >= lifetime_ticks && !warned {
+                        animations
+                            .insert(entity, SpriteAnimationComponent::new(APPLE_EXPIRING))
+                            .unwrap();
+                    }
+                }
+                AppleBehaviour::Wandering { every_ticks } => {
+                    if age == 0 || age % every_ticks.max(1) != 0 {
+                        continue;
+                    }
+                    let free_neighbours: Vec<glm::IVec2> = [
+                        glm::vec2(1, 0),
+                        glm::vec2(-1, 0),
+                        glm::vec2(0, 1),
+                        glm::vec2(0, -1),
+                    ]
+                    .iter()
+                    .map(|offset| position.position + offset)
+                    .filter(|cell| grid.contains(cell) && grid.is_free(cell))
+                    .collect();
+                    if let Some(target) = free_neighbours.choose(&mut rng.0) {
+                        grid.clear(&position.position);
+                        position.position = *target;
+                        grid.set(target, Cell::Apple(entity));
+                    }
+                }
+            }
+        }
+    }
+}
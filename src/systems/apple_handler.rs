@@ -1,76 +1,249 @@
-use amethyst::{
-    core::Transform, derive::SystemDesc, ecs::prelude::*, prelude::*, renderer::SpriteRender,
-};
-use rand::prelude::*;
+use amethyst::{derive::SystemDesc, ecs::prelude::*, renderer::SpriteRender, shrev::EventChannel};
 
+use crate::animation::APPLE_IDLE;
 use crate::components::{
-    AppleComponent, GamePositionComponent, SnakeHeadComponent, SnakePartComponent,
-};
-use crate::snake::{
-    AppleWasEaten, Direction, NextDirection, ARENA_PLAYABLE_HEIGHT, ARENA_PLAYABLE_WIDTH,
+    AppleComponent, GamePositionComponent, SnakeComponent, SpriteAnimationComponent,
 };
+use crate::events::GameEvent;
+use crate::fruits::{AppleBehaviour, FruitTable};
+use crate::occupancy_grid::{Cell, OccupancyGrid};
+use crate::ruleset::Ruleset;
+use crate::snake::{GameRng, GameState, SnakeSprites, TickClock};
 
+/// Applies the fruits eaten by the snakes, each one to the snake whose head reached it, and
+/// publishes them as `GameEvent::AppleEaten`. The apples then move elsewhere as new fruits once
+/// they are eaten or rotten, or are removed when they are eaten and do not respawn.
 #[derive(SystemDesc)]
 pub struct AppleHandlerSystem;
 
 impl<'s> System<'s> for AppleHandlerSystem {
     type SystemData = (
+        Entities<'s>,
         WriteStorage<'s, GamePositionComponent>,
-        ReadStorage<'s, SnakeHeadComponent>,
-        ReadStorage<'s, SnakePartComponent>,
-        ReadStorage<'s, AppleComponent>,
-        WriteExpect<'s, AppleWasEaten>,
+        WriteStorage<'s, AppleComponent>,
+        WriteStorage<'s, SnakeComponent>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, SpriteAnimationComponent>,
+        WriteExpect<'s, OccupancyGrid>,
+        Write<'s, EventChannel<GameEvent>>,
+        WriteExpect<'s, GameState>,
+        ReadExpect<'s, Ruleset>,
+        WriteExpect<'s, GameRng>,
+        ReadExpect<'s, FruitTable>,
+        ReadExpect<'s, SnakeSprites>,
+        ReadExpect<'s, TickClock>,
     );
 
     fn run(
         &mut self,
-        (mut game_positions, snake_heads, snake_parts, apples, mut apple_was_eaten): Self::SystemData,
+        (
+            entities,
+            mut game_positions,
+            mut apples,
+            mut snakes,
+            mut sprite_renders,
+            mut animations,
+            mut grid,
+            mut events,
+            mut game_state,
+            ruleset,
+            mut rng,
+            fruit_table,
+            snake_sprites,
+            tick_clock,
+        ): Self::SystemData,
     ) {
-        let mut snake_head_positions: Vec<glm::IVec2> = vec![];
-        for (_, game_position) in (&snake_heads, &game_positions).join() {
-            snake_head_positions.push(game_position.position.clone());
+        if *game_state != GameState::Playing {
+            return;
         }
 
-        let mut snake_ate_apple = false;
+        let eaten: Vec<(Entity, glm::IVec2, Entity)> = (&entities, &apples, &game_positions)
+            .join()
+            .filter_map(|(apple, _, position)| match grid.get(&position.position) {
+                Cell::Snake { snake, .. } => Some((apple, position.position, snake)),
+                _ => None,
+            })
+            .collect();
+        let rotten: Vec<Entity> = (&entities, &apples)
+            .join()
+            .filter(|(apple, component)| {
+                let expired = match component.behaviour {
+                    AppleBehaviour::Expiring { lifetime_ticks, .. } => {
+                        tick_clock.ticks - component.spawned_at >= lifetime_ticks
+                    }
+                    _ => false,
+                };
+                expired && eaten.iter().all(|(eaten_apple, ..)| eaten_apple != apple)
+            })
+            .map(|(apple, _)| apple)
+            .collect();
 
-        for (_, apple_position) in (&apples, &mut game_positions).join() {
-            for snake_head_position in &snake_head_positions {
-                if *snake_head_position == apple_position.position {
-                    snake_ate_apple = true;
+        for &(apple, eaten_at, eaten_by) in &eaten {
+            let kind = match apples.get(apple) {
+                Some(apple) => apple.fruit,
+                None => continue,
+            };
+            events.single_write(GameEvent::AppleEaten {
+                snake: eaten_by,
+                fruit: kind,
+                position: eaten_at,
+            });
+            if let (Some(fruit), Some(snake)) =
+                (fruit_table.fruits.get(kind), snakes.get_mut(eaten_by))
+            {
+                snake.pending_growth += ruleset.growth(fruit);
+                if fruit.deadly {
+                    events.single_write(GameEvent::Died {
+                        snake: eaten_by,
+                        position: eaten_at,
+                    });
+                    snake.release_cells(eaten_by, &mut grid);
+                    if !snake.lose_life(
+                        eaten_by,
+                        &entities,
+                        &mut game_positions,
+                        &mut grid,
+                        &mut rng,
+                    ) {
+                        *game_state = GameState::GameOver;
+                        return;
+                    }
                 }
             }
         }
 
-        if snake_ate_apple {
-            let mut snake_positions = vec![];
-            let mut new_apple_position = (0, 0);
-            for (_, game_position) in (&snake_parts, &game_positions).join() {
-                snake_positions.push(game_position.position);
-            }
-
-            new_apple_position = get_new_apple_position(snake_positions);
+        let heads: Vec<glm::IVec2> = (&snakes).join().map(|snake| snake.head()).collect();
+        let mut remaining = (&apples).join().count();
+        let mut removed = false;
+        let replaced = eaten
+            .iter()
+            .map(|&(apple, ..)| (apple, true))
+            .chain(rotten.into_iter().map(|apple| (apple, false)));
+        for (apple, was_eaten) in replaced {
+            let respawn = apples.get(apple).map_or(false, |apple| apple.respawn);
+            let outcome = replacement(was_eaten, respawn, || {
+                ruleset.pick_apple_cell(&grid, &heads, &mut rng.0)
+            });
+            let position = match (outcome, game_positions.get_mut(apple)) {
+                (Replacement::Stay, _) => continue,
+                (Replacement::MoveTo(new_position), Some(position)) => {
+                    if grid.get(&position.position) == Cell::Apple(apple) {
+                        grid.clear(&position.position);
+                    }
+                    position.position = new_position;
+                    new_position
+                }
+                (_, position) => {
+                    if let Some(position) = position {
+                        if grid.get(&position.position) == Cell::Apple(apple) {
+                            grid.clear(&position.position);
+                        }
+                    }
+                    entities.delete(apple).unwrap();
+                    remaining -= 1;
+                    removed = true;
+                    continue;
+                }
+            };
+            grid.set(&position, Cell::Apple(apple));
 
-            for (_, apple_position) in (&apples, &mut game_positions).join() {
-                apple_position.position = glm::vec2(new_apple_position.0, new_apple_position.1);
+            let fruit = fruit_table.pick(&mut rng.0).unwrap_or(0);
+            let behaviour = fruit_table
+                .fruits
+                .get(fruit)
+                .map(|fruit| fruit.behaviour.clone())
+                .unwrap_or_default();
+            apples
+                .insert(
+                    apple,
+                    AppleComponent {
+                        fruit,
+                        spawned_at: tick_clock.ticks,
+                        respawn,
+                        behaviour,
+                    },
+                )
+                .unwrap();
+            let sprite_render = fruit_table
+                .fruits
+                .get(fruit)
+                .and_then(|fruit| snake_sprites.get_sprite_by_name(&fruit.sprite));
+            if let Some(sprite_render) = sprite_render {
+                sprite_renders.insert(apple, sprite_render).unwrap();
             }
+            animations
+                .insert(apple, SpriteAnimationComponent::new(APPLE_IDLE))
+                .unwrap();
+        }
 
-            apple_was_eaten.was_eaten = true;
+        // Only eaten apples are removed, and a board that never had any apple, like with a ruleset
+        // without apples, is not won.
+        if removed && remaining == 0 {
+            events.single_write(GameEvent::LevelCompleted);
+            *game_state = GameState::Won;
         }
     }
 }
 
-fn get_new_apple_position(snake_positions: Vec<glm::IVec2>) -> (i32, i32) {
-    let mut rng = thread_rng();
-    loop {
-        let x = rng.gen_range(0, ARENA_PLAYABLE_WIDTH) + 1;
-        let y = rng.gen_range(0, ARENA_PLAYABLE_HEIGHT) + 1;
+/// What becomes of an apple once it is eaten or rotten.
+#[derive(Debug, PartialEq)]
+enum Replacement {
+    /// The apple moves to the cell as a new fruit.
+    MoveTo(glm::IVec2),
+    /// The apple stays where it is.
+    Stay,
+    /// The apple is removed from the board, which counts towards completing the level.
+    Remove,
+}
+
+/// Decides what becomes of an apple, calling `pick_cell` for a free cell only when the apple may
+/// move, so that the random numbers drawn do not depend on apples which cannot.
+fn replacement(
+    was_eaten: bool,
+    respawn: bool,
+    pick_cell: impl FnOnce() -> Option<glm::IVec2>,
+) -> Replacement {
+    match (was_eaten, respawn) {
+        (true, true) => pick_cell().map_or(Replacement::Remove, Replacement::MoveTo),
+        (true, false) => Replacement::Remove,
+        // A rotten fruit always moves, even a level apple which has to be eaten to complete the
+        // level, and stays where it is when there is nowhere else to go.
+        (false, _) => pick_cell().map_or(Replacement::Stay, Replacement::MoveTo),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let position_ok = snake_positions
-            .iter()
-            .all(|position| position.x != x || position.y != y);
+    #[test]
+    fn eaten_apples_respawn_on_a_free_cell() {
+        let cell = glm::vec2(3, 4);
 
-        if position_ok {
-            return (x, y);
-        }
+        assert_eq!(
+            replacement(true, true, || Some(cell)),
+            Replacement::MoveTo(cell)
+        );
+        assert_eq!(replacement(true, true, || None), Replacement::Remove);
+        assert_eq!(
+            replacement(true, false, || panic!("no cell is needed")),
+            Replacement::Remove
+        );
+    }
+
+    #[test]
+    fn rotten_apples_move_whether_they_respawn_or_not() {
+        let cell = glm::vec2(3, 4);
+
+        assert_eq!(
+            replacement(false, true, || Some(cell)),
+            Replacement::MoveTo(cell)
+        );
+        assert_eq!(replacement(false, true, || None), Replacement::Stay);
+        assert_eq!(
+            replacement(false, false, || Some(cell)),
+            Replacement::MoveTo(cell)
+        );
+        assert_eq!(replacement(false, false, || None), Replacement::Stay);
     }
 }
@@ -0,0 +1,156 @@
+use amethyst::{
+    core::{math::Vector2, timing::Time, Transform},
+    derive::SystemDesc,
+    ecs::prelude::*,
+    renderer::Camera,
+    window::ScreenDimensions,
+};
+use rand::prelude::*;
+
+use crate::components::{CameraRigComponent, SnakeComponent};
+use crate::occupancy_grid::OccupancyGrid;
+use crate::settings::{CameraMode, Settings};
+use crate::snake::{board_center, board_size, GameState, TileSize};
+
+#[derive(SystemDesc)]
+pub struct CameraSystem;
+
+impl<'s> System<'s> for CameraSystem {
+    type SystemData = (
+        WriteStorage<'s, Camera>,
+        WriteStorage<'s, CameraRigComponent>,
+        WriteStorage<'s, Transform>,
+        ReadStorage<'s, SnakeComponent>,
+        ReadExpect<'s, OccupancyGrid>,
+        ReadExpect<'s, Settings>,
+        ReadExpect<'s, ScreenDimensions>,
+        ReadExpect<'s, GameState>,
+        ReadExpect<'s, TileSize>,
+        Read<'s, Time>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut cameras,
+            mut rigs,
+            mut transforms,
+            snakes,
+            grid,
+            settings,
+            dimensions,
+            game_state,
+            tile_size,
+            time,
+        ): Self::SystemData,
+    ) {
+        let heads: Vec<Vector2<f32>> = (&snakes)
+            .join()
+            .filter_map(|snake| transforms.get(snake.segments[0]))
+            .map(|transform| transform.translation().xy())
+            .collect();
+
+        let tile_size = tile_size.size;
+        let board_size = board_size(&grid, tile_size);
+        let screen_size = Vector2::new(dimensions.width(), dimensions.height());
+        if screen_size.x <= 0.0 || screen_size.y <= 0.0 {
+            return;
+        }
+        let delta = time.delta_seconds();
+
+        for (camera, rig, transform) in (&mut cameras, &mut rigs, &mut transforms).join() {
+            // The view keeps the aspect ratio of the window, so the board is letterboxed instead
+            // of stretched, and it is recomputed every frame to follow window resizes.
+            let (view_size, focus) = match settings.camera {
+                CameraMode::FitToBoard => {
+                    let scale = (board_size.x / screen_size.x).max(board_size.y / screen_size.y);
+                    (screen_size * scale, board_center(&grid, tile_size))
+                }
+                CameraMode::FollowHead {
+                    visible_rows,
+                    dead_zone,
+                    smoothing,
+                } => {
+                    let view_size = screen_size * (visible_rows * tile_size / screen_size.y);
+                    let current = rig.focus.unwrap_or_else(|| board_center(&grid, tile_size));
+                    let target = if heads.is_empty() {
+                        current
+                    } else {
+                        let sum = heads
+                            .iter()
+                            .fold(Vector2::new(0.0, 0.0), |sum, head| sum + head);
+                        sum / heads.len() as f32
+                    };
+                    let desired = Vector2::new(
+                        follow_axis(current.x, target.x, dead_zone.0 * tile_size * 0.5),
+                        follow_axis(current.y, target.y, dead_zone.1 * tile_size * 0.5),
+                    );
+                    let blend = 1.0 - (-smoothing * delta).exp();
+                    let focus = current + (desired - current) * blend;
+                    (
+                        view_size,
+                        clamp_to_board(focus, &view_size, &grid, tile_size),
+                    )
+                }
+            };
+            rig.focus = Some(focus);
+
+            let mut offset = Vector2::new(0.0, 0.0);
+            if let Some(shake) = &settings.screen_shake {
+                if *game_state == GameState::GameOver && !rig.has_shaken {
+                    rig.has_shaken = true;
+                    rig.shake_remaining = shake.duration;
+                }
+                if rig.shake_remaining > 0.0 {
+                    let strength = shake.intensity * rig.shake_remaining / shake.duration;
+                    let mut rng = thread_rng();
+                    offset = Vector2::new(
+                        rng.gen_range(-strength, strength),
+                        rng.gen_range(-strength, strength),
+                    );
+                    rig.shake_remaining -= delta;
+                }
+            }
+
+            *camera = Camera::standard_2d(view_size.x, view_size.y);
+            let z = transform.translation().z;
+            transform.set_translation_xyz(focus.x + offset.x, focus.y + offset.y, z);
+        }
+    }
+}
+
+fn follow_axis(current: f32, target: f32, half_dead_zone: f32) -> f32 {
+    if target > current + half_dead_zone {
+        target - half_dead_zone
+    } else if target < current - half_dead_zone {
+        target + half_dead_zone
+    } else {
+        current
+    }
+}
+
+fn clamp_to_board(
+    focus: Vector2<f32>,
+    view_size: &Vector2<f32>,
+    grid: &OccupancyGrid,
+    tile_size: f32,
+) -> Vector2<f32> {
+    let center = board_center(grid, tile_size);
+    let half_board = board_size(grid, tile_size) * 0.5;
+    let half_view = view_size * 0.5;
+
+    let clamp_axis = |value: f32, center: f32, half_board: f32, half_view: f32| {
+        if half_view >= half_board {
+            center
+        } else {
+            value
+                .max(center - half_board + half_view)
+                .min(center + half_board - half_view)
+        }
+    };
+
+    Vector2::new(
+        clamp_axis(focus.x, center.x, half_board.x, half_view.x),
+        clamp_axis(focus.y, center.y, half_board.y, half_view.y),
+    )
+}
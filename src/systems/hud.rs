@@ -0,0 +1,46 @@
+use amethyst::{derive::SystemDesc, ecs::prelude::*, ui::UiText};
+
+use crate::components::{SnakeComponent, TimedEffectComponent};
+use crate::snake::Hud;
+
+/// Shows the score of every player and the effects acting on their snake.
+#[derive(SystemDesc)]
+pub struct HudSystem;
+
+impl<'s> System<'s> for HudSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, SnakeComponent>,
+        ReadStorage<'s, TimedEffectComponent>,
+        WriteStorage<'s, UiText>,
+        Option<Read<'s, Hud>>,
+    );
+
+    fn run(&mut self, (entities, snakes, effects, mut texts, hud): Self::SystemData) {
+        let text = match hud.and_then(|hud| texts.get_mut(hud.entity)) {
+            Some(text) => text,
+            None => return,
+        };
+
+        let mut players: Vec<(usize, String)> = (&entities, &snakes)
+            .join()
+            .map(|(entity, snake)| {
+                let mut label = format!("P{}: {}", snake.player + 1, snake.score);
+                if snake.lives > 1 {
+                    label += &format!("  x{}", snake.lives);
+                }
+                for effect in (&effects).join().filter(|effect| effect.target == entity) {
+                    label += &format!("  {} {}", effect.label, effect.remaining_ticks);
+                }
+                (snake.player, label)
+            })
+            .collect();
+        players.sort();
+
+        text.text = players
+            .into_iter()
+            .map(|(_, label)| label)
+            .collect::<Vec<_>>()
+            .join("     ");
+    }
+}
@@ -1,33 +1,113 @@
+use std::collections::HashMap;
+
 use amethyst::{
-    derive::SystemDesc,
+    core::math::Vector2,
     ecs::prelude::*,
     input::{InputHandler, StringBindings},
-    renderer::SpriteRender,
 };
 
-use crate::snake::{Direction, NextDirection};
+use crate::components::SnakeComponent;
+use crate::occupancy_grid::OccupancyGrid;
+use crate::settings::{ControlScheme, PlayerSettings, Settings, Steering};
+use crate::snake::Direction;
 
-#[derive(SystemDesc)]
-pub struct InputSystem;
+#[derive(Default)]
+pub struct InputSystem {
+    /// Whether the `left` and `right` actions of each relative steering snake were down on the
+    /// previous frame, so that holding them turns only once.
+    turns_held: HashMap<Entity, (bool, bool)>,
+}
 
 impl<'s> System<'s> for InputSystem {
     type SystemData = (
-        WriteExpect<'s, NextDirection>,
+        Entities<'s>,
+        WriteStorage<'s, SnakeComponent>,
         Read<'s, InputHandler<StringBindings>>,
+        ReadExpect<'s, Settings>,
+        ReadExpect<'s, OccupancyGrid>,
     );
 
-    fn run(&mut self, (mut next_direction, input): Self::SystemData) {
-        if input.action_is_down("up").unwrap() {
-            next_direction.direction = Direction::Up;
-        }
-        if input.action_is_down("down").unwrap() {
-            next_direction.direction = Direction::Down;
-        }
-        if input.action_is_down("right").unwrap() {
-            next_direction.direction = Direction::Right;
-        }
-        if input.action_is_down("left").unwrap() {
-            next_direction.direction = Direction::Left;
+    fn run(&mut self, (entities, mut snakes, input, settings, grid): Self::SystemData) {
+        let mut turns_held = HashMap::new();
+        for (entity, snake) in (&entities, &mut snakes).join() {
+            let player = settings
+                .players
+                .get(snake.player)
+                .cloned()
+                .unwrap_or_else(PlayerSettings::default);
+            let controls = player.controls;
+
+            let direction = match player.steering {
+                Steering::Absolute => pressed_direction(&input, controls)
+                    .or_else(|| stick_direction(&input, controls, settings.stick_dead_zone)),
+                Steering::Relative => {
+                    let held = (
+                        is_down(&input, controls, "left"),
+                        is_down(&input, controls, "right"),
+                    );
+                    let (was_left, was_right) =
+                        self.turns_held.get(&entity).copied().unwrap_or(held);
+                    turns_held.insert(entity, held);
+
+                    // Turning from the direction of the last move rather than from the pending
+                    // one, two turns the same way before the snake moves cannot turn it around.
+                    let heading = snake
+                        .heading(&grid)
+                        .unwrap_or_else(|| snake.next_direction.clone());
+                    if held.0 && !was_left {
+                        Some(heading.turned_left())
+                    } else if held.1 && !was_right {
+                        Some(heading.turned_right())
+                    } else {
+                        None
+                    }
+                }
+            };
+            if let Some(direction) = direction {
+                snake.next_direction = direction;
+            }
         }
+        self.turns_held = turns_held;
+    }
+}
+
+fn is_down(input: &InputHandler<StringBindings>, controls: ControlScheme, action: &str) -> bool {
+    controls.action(action).map_or(false, |action| {
+        input.action_is_down(&action).unwrap_or(false)
+    })
+}
+
+fn pressed_direction(
+    input: &InputHandler<StringBindings>,
+    controls: ControlScheme,
+) -> Option<Direction> {
+    if is_down(input, controls, "left") {
+        Some(Direction::Left)
+    } else if is_down(input, controls, "right") {
+        Some(Direction::Right)
+    } else if is_down(input, controls, "down") {
+        Some(Direction::Down)
+    } else if is_down(input, controls, "up") {
+        Some(Direction::Up)
+    } else {
+        None
+    }
+}
+
+fn stick_direction(
+    input: &InputHandler<StringBindings>,
+    controls: ControlScheme,
+    dead_zone: f32,
+) -> Option<Direction> {
+    let (x_axis, y_axis) = controls.stick_axes()?;
+    let stick = Vector2::new(
+        input.axis_value(&x_axis).unwrap_or(0.0),
+        input.axis_value(&y_axis).unwrap_or(0.0),
+    );
+
+    if stick.norm() < dead_zone {
+        None
+    } else {
+        Direction::dominant(stick)
     }
 }
@@ -1,13 +1,49 @@
+pub use achievements::AchievementsSystemDesc;
+pub use apple_behaviour::AppleBehaviourSystem;
 pub use apple_handler::AppleHandlerSystem;
+pub use camera::CameraSystem;
+pub use hud::HudSystem;
 pub use input::InputSystem;
+pub use mouse_steering::MouseSteeringSystem;
 pub use move_snake::MoveSnakeSystem;
+pub use music::MusicSystem;
+pub use particle_trigger::ParticleTriggerSystemDesc;
+pub use particles::ParticleSystem;
+pub use power_ups::PowerUpSystem;
+pub use replay::ReplaySystem;
+pub use score::ScoreSystemDesc;
+pub use snake_animation::SnakeAnimationSystemDesc;
 pub use snake_collision::SnakeCollisionSystem;
+pub use snake_interpolation::SnakeInterpolationSystem;
 pub use snake_render::SnakeRenderSystem;
+pub use sound_effects::SoundEffectsSystemDesc;
+pub use speed_curve::SpeedCurveSystemDesc;
+pub use sprite_animation::SpriteAnimationSystem;
+pub use stats::StatsSystemDesc;
+pub use timed_effects::TimedEffectSystem;
 pub use transform_positions::TransformPositionsSystem;
 
+mod achievements;
+mod apple_behaviour;
 mod apple_handler;
+mod camera;
+mod hud;
 mod input;
+mod mouse_steering;
 mod move_snake;
+mod music;
+mod particle_trigger;
+mod particles;
+mod power_ups;
+mod replay;
+mod score;
+mod snake_animation;
 mod snake_collision;
+mod snake_interpolation;
 mod snake_render;
+mod sound_effects;
+mod speed_curve;
+mod sprite_animation;
+mod stats;
+mod timed_effects;
 mod transform_positions;
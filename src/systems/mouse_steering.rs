@@ -0,0 +1,113 @@
+use amethyst::{
+    core::{
+        math::{Point3, Vector2},
+        Transform,
+    },
+    ecs::prelude::*,
+    input::{InputHandler, StringBindings},
+    renderer::Camera,
+    window::ScreenDimensions,
+    winit::MouseButton,
+};
+
+use crate::components::SnakeComponent;
+use crate::occupancy_grid::OccupancyGrid;
+use crate::settings::{ControlScheme, Settings};
+use crate::snake::{Direction, TileSize};
+
+/// Steers the snakes of the players using the `Mouse` or `Swipe` control scheme.
+#[derive(Default)]
+pub struct MouseSteeringSystem {
+    swipe_start: Option<Vector2<f32>>,
+}
+
+impl<'s> System<'s> for MouseSteeringSystem {
+    type SystemData = (
+        WriteStorage<'s, SnakeComponent>,
+        ReadStorage<'s, Camera>,
+        ReadStorage<'s, Transform>,
+        Read<'s, InputHandler<StringBindings>>,
+        ReadExpect<'s, ScreenDimensions>,
+        ReadExpect<'s, Settings>,
+        ReadExpect<'s, TileSize>,
+        ReadExpect<'s, OccupancyGrid>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut snakes,
+            cameras,
+            transforms,
+            input,
+            dimensions,
+            settings,
+            tile_size,
+            grid,
+        ): Self::SystemData,
+    ) {
+        let cursor = match input.mouse_position() {
+            Some((x, y)) if input.mouse_button_is_down(MouseButton::Left) => Vector2::new(x, y),
+            _ => {
+                self.swipe_start = None;
+                return;
+            }
+        };
+
+        let swipe = match self.swipe_start {
+            Some(start) if (cursor - start).norm() >= settings.swipe_distance => {
+                self.swipe_start = Some(cursor);
+                // The screen y axis points down, unlike the board one.
+                Direction::dominant(Vector2::new(cursor.x - start.x, start.y - cursor.y))
+            }
+            Some(_) => None,
+            None => {
+                self.swipe_start = Some(cursor);
+                None
+            }
+        };
+
+        let cursor_in_world = (&cameras, &transforms)
+            .join()
+            .next()
+            .map(|(camera, transform)| {
+                camera
+                    .screen_to_world_point(
+                        Point3::new(cursor.x, cursor.y, 0.0),
+                        dimensions.diagonal(),
+                        transform,
+                    )
+                    .coords
+                    .xy()
+            });
+
+        for snake in (&mut snakes).join() {
+            let controls = settings
+                .players
+                .get(snake.player)
+                .map_or(ControlScheme::default(), |player| player.controls);
+
+            let direction = match controls {
+                ControlScheme::Mouse => cursor_in_world.and_then(|cursor| {
+                    let head = transforms.get(snake.segments[0])?.translation().xy();
+                    let offset = cursor - head;
+                    // Pointing at the head itself keeps the current direction.
+                    if offset.x.abs().max(offset.y.abs()) < tile_size.size / 2.0 {
+                        None
+                    } else {
+                        Direction::dominant(offset)
+                    }
+                }),
+                ControlScheme::Swipe => swipe.clone(),
+                _ => None,
+            };
+            // Pointing or swiping back towards the tail would turn the snake onto its own neck.
+            let heading = snake.heading(&grid);
+            if let Some(direction) = direction {
+                if heading.map_or(true, |heading| direction != heading.opposite()) {
+                    snake.next_direction = direction;
+                }
+            }
+        }
+    }
+}
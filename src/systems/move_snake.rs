@@ -1,9 +1,11 @@
-use amethyst::{
-    core::Transform, derive::SystemDesc, ecs::prelude::*, prelude::*, renderer::SpriteRender,
-};
+use amethyst::{core::Transform, derive::SystemDesc, ecs::prelude::*, shrev::EventChannel};
 
-use crate::components::{GamePositionComponent, SnakeHeadComponent, SnakePartComponent};
-use crate::snake::{AppleWasEaten, Direction, NextDirection, SnakeSprites, SnakeSpritesKeys};
+use crate::components::{GamePositionComponent, SnakeComponent, TimedEffectComponent};
+use crate::events::GameEvent;
+use crate::occupancy_grid::{Cell, OccupancyGrid};
+use crate::power_ups::Effect;
+use crate::ruleset::{Ruleset, SelfCollision};
+use crate::snake::{GameState, SnakeSprites, SnakeSpritesKeys, TickClock};
 
 #[derive(SystemDesc)]
 pub struct MoveSnakeSystem;
@@ -11,96 +13,142 @@ pub struct MoveSnakeSystem;
 impl<'s> System<'s> for MoveSnakeSystem {
     type SystemData = (
         Entities<'s>,
-        WriteStorage<'s, SnakePartComponent>,
+        WriteStorage<'s, SnakeComponent>,
         WriteStorage<'s, GamePositionComponent>,
-        ReadStorage<'s, SnakeHeadComponent>,
-        WriteExpect<'s, NextDirection>,
-        WriteExpect<'s, AppleWasEaten>,
+        ReadStorage<'s, TimedEffectComponent>,
+        WriteExpect<'s, TickClock>,
+        WriteExpect<'s, OccupancyGrid>,
         ReadExpect<'s, SnakeSprites>,
+        ReadExpect<'s, GameState>,
         Read<'s, LazyUpdate>,
+        Write<'s, EventChannel<GameEvent>>,
+        ReadExpect<'s, Ruleset>,
     );
 
     fn run(
         &mut self,
         (
             entities,
-            mut snake_parts,
+            mut snakes,
             mut positions,
-            snake_heads,
-            mut next_direction,
-            mut apple_was_eaten,
+            effects,
+            mut tick_clock,
+            mut grid,
             snake_sprites,
+            game_state,
             updater,
+            mut events,
+            ruleset,
         ): Self::SystemData,
     ) {
-        let direction = next_direction.direction.clone();
-        let stop_watch = &mut next_direction.time_since_last_action;
-        if stop_watch.elapsed().as_millis() > 200 {
-            let mut new_head_position = glm::vec2(0, 0);
-            let mut old_head_position = glm::vec2(0, 0);
-            let mut next_option_entity: Option<Entity> = None;
-            for (part, position, _) in (&mut snake_parts, &mut positions, &snake_heads).join() {
-                old_head_position = position.position.clone();
-                new_head_position = old_head_position.clone();
-                match direction {
-                    Direction::Up => new_head_position.y = new_head_position.y + 1,
-                    Direction::Right => new_head_position.x = new_head_position.x + 1,
-                    Direction::Down => new_head_position.y = new_head_position.y - 1,
-                    Direction::Left => new_head_position.x = new_head_position.x - 1,
-                };
-                position.position = new_head_position.clone();
-                next_option_entity = part.next_snake_part;
-            }
+        if *game_state != GameState::Playing {
+            return;
+        }
 
-            let mut next_part_position = old_head_position;
-            let mut last_entity_optional = None;
-            loop {
-                if let Some(entity) = next_option_entity {
-                    let next_position = positions.get_mut(entity).unwrap();
-                    std::mem::swap(&mut next_position.position, &mut next_part_position);
+        if tick_clock.is_due() {
+            let mut heads = vec![];
+            for (snake_entity, snake) in (&entities, &mut snakes).join() {
+                let new_head_position =
+                    ruleset.next_cell(&grid, snake.head(), &snake.next_direction);
+                let turned = snake.len() > 1
+                    && ruleset.next_cell(&grid, snake.body[1], &snake.next_direction)
+                        != snake.head();
+                if turned {
+                    events.single_write(GameEvent::Turned {
+                        snake: snake_entity,
+                        direction: snake.next_direction.clone(),
+                    });
+                }
+                let length = snake.len();
 
-                    let next_part = snake_parts.get_mut(entity).unwrap();
-                    next_option_entity = next_part.next_snake_part;
-                    last_entity_optional = Some(entity);
-                } else {
-                    match last_entity_optional {
-                        Some(last_entity) if apple_was_eaten.was_eaten => {
-                            let new_piece_entity = entities.create();
-                            snake_parts.insert(
-                                new_piece_entity,
-                                SnakePartComponent {
-                                    next_snake_part: None,
-                                },
-                            );
-                            positions.insert(
-                                new_piece_entity,
-                                GamePositionComponent::new(
-                                    next_part_position.x,
-                                    next_part_position.y,
-                                ),
-                            );
-                            updater.insert(new_piece_entity, {
-                                let mut transform = Transform::default();
-                                transform.set_translation_z(0.5);
-                                transform
-                            });
-                            updater.insert(
-                                new_piece_entity,
-                                snake_sprites.get_sprite_clone(SnakeSpritesKeys::SnakeTail),
-                            );
+                if snake.pending_growth > 0 {
+                    let new_piece_entity = entities.create();
+                    positions
+                        .insert(
+                            new_piece_entity,
+                            GamePositionComponent::new(new_head_position.x, new_head_position.y),
+                        )
+                        .unwrap();
+                    updater.insert(new_piece_entity, {
+                        let mut transform = Transform::default();
+                        transform.set_translation_z(0.5);
+                        transform
+                    });
+                    updater.insert(
+                        new_piece_entity,
+                        snake_sprites
+                            .get_skin_sprite_clone(snake.player, SnakeSpritesKeys::SnakeHead),
+                    );
+                    snake.push_head(new_head_position, new_piece_entity);
 
-                            let next_part = snake_parts.get_mut(last_entity).unwrap();
-                            next_part.next_snake_part = Some(new_piece_entity);
+                    snake.previous_tail = None;
+                    snake.pending_growth -= 1;
+                } else if let Some((old_tail_position, tail_entity)) = snake.pop_tail() {
+                    let tail_cell = Cell::Snake {
+                        snake: snake_entity,
+                        segment: snake.segment_serial(snake.len()),
+                    };
+                    if grid.get(&old_tail_position) == tail_cell {
+                        grid.clear(&old_tail_position);
+                    }
+                    snake.previous_tail = Some(old_tail_position);
+                    if let Some(tail_position) = positions.get_mut(tail_entity) {
+                        tail_position.position = new_head_position;
+                    }
+                    snake.push_head(new_head_position, tail_entity);
 
-                            apple_was_eaten.was_eaten = false;
-                        }
-                        _ => {}
+                    if snake.pending_growth < 0 {
+                        snake.shrink(snake_entity, 1, &entities, &mut grid);
+                        // Whatever is left to lose is dropped once the snake cannot get shorter.
+                        snake.pending_growth = if snake.len() > 2 {
+                            snake.pending_growth + 1
+                        } else {
+                            0
+                        };
                     }
-                    break;
+                }
+
+                if snake.len() != length {
+                    events.single_write(GameEvent::Grew {
+                        snake: snake_entity,
+                        length: snake.len(),
+                    });
+                }
+
+                heads.push((snake_entity, new_head_position, snake.head_serial));
+            }
+
+            let ghosts: Vec<Entity> = (&effects)
+                .join()
+                .filter(|effect| effect.effect == Effect::Ghost)
+                .map(|effect| effect.target)
+                .collect();
+
+            // The heads are placed once every snake moved, so that the tails left on this move
+            // are free whatever the order of the snakes. A blocked cell keeps its occupant and a
+            // cell reached by several heads is left to none of them, so that the collision system
+            // sees every snake that ran into something. A snake passing through its own body
+            // takes the cell over, so that it stays taken once the older segment leaves it.
+            for &(snake, position, segment) in &heads {
+                let shared = heads
+                    .iter()
+                    .filter(|(_, other, _)| *other == position)
+                    .count()
+                    > 1;
+                let passes_through = match grid.get(&position) {
+                    Cell::Snake { snake: owner, .. } if owner == snake => {
+                        ghosts.contains(&snake)
+                            || ruleset.self_collision == SelfCollision::PassThrough
+                    }
+                    _ => false,
+                };
+                if !shared && (passes_through || !grid.get(&position).is_blocking()) {
+                    grid.set(&position, Cell::Snake { snake, segment });
                 }
             }
 
-            stop_watch.restart();
+            tick_clock.restart();
+            tick_clock.ticks += 1;
         }
     }
 }
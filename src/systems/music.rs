@@ -0,0 +1,46 @@
+use amethyst::{
+    assets::AssetStorage,
+    audio::{output::Output, AudioSink, Source},
+    ecs::prelude::*,
+};
+use log::warn;
+
+use crate::audio::{Music, MusicMode};
+use crate::settings::Settings;
+
+#[derive(Default)]
+pub struct MusicSystem {
+    playing: Option<MusicMode>,
+}
+
+impl<'s> System<'s> for MusicSystem {
+    type SystemData = (
+        Option<Read<'s, Output>>,
+        Option<Write<'s, AudioSink>>,
+        Read<'s, AssetStorage<Source>>,
+        WriteExpect<'s, Music>,
+        ReadExpect<'s, Settings>,
+    );
+
+    fn run(&mut self, (output, sink, storage, mut music, settings): Self::SystemData) {
+        let (output, mut sink) = match (output, sink) {
+            (Some(output), Some(sink)) => (output, sink),
+            _ => return,
+        };
+
+        // A new sink drops the track of the previous mode instead of waiting for it to end.
+        if self.playing != Some(music.mode) {
+            *sink = AudioSink::new(&output);
+            self.playing = Some(music.mode);
+        }
+        sink.set_volume(settings.volume.master * settings.volume.music);
+
+        if sink.empty() {
+            if let Some(track) = music.next_track().and_then(|handle| storage.get(&handle)) {
+                if sink.append(track).is_err() {
+                    warn!("Failed to decode a music track");
+                }
+            }
+        }
+    }
+}
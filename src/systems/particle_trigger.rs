@@ -0,0 +1,78 @@
+use amethyst::{
+    core::Transform,
+    derive::SystemDesc,
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::components::ParticleEmitterComponent;
+use crate::events::GameEvent;
+use crate::particles::{APPLE_EATEN, DEATH};
+use crate::snake::TileSize;
+
+/// Spawns a particle emitter where an apple was eaten or a snake died.
+#[derive(SystemDesc)]
+#[system_desc(name(ParticleTriggerSystemDesc))]
+pub struct ParticleTriggerSystem {
+    #[system_desc(event_channel_reader)]
+    reader_id: ReaderId<GameEvent>,
+}
+
+impl ParticleTriggerSystem {
+    pub fn new(reader_id: ReaderId<GameEvent>) -> Self {
+        ParticleTriggerSystem { reader_id }
+    }
+}
+
+impl<'s> System<'s> for ParticleTriggerSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, ParticleEmitterComponent>,
+        WriteStorage<'s, Transform>,
+        Read<'s, EventChannel<GameEvent>>,
+        ReadExpect<'s, TileSize>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, mut emitters, mut transforms, events, tile_size): Self::SystemData,
+    ) {
+        for event in events.read(&mut self.reader_id) {
+            let (preset, position) = match event {
+                GameEvent::AppleEaten { position, .. } => (APPLE_EATEN, *position),
+                GameEvent::Died { position, .. } => (DEATH, *position),
+                _ => continue,
+            };
+            spawn_emitter(
+                &entities,
+                &mut emitters,
+                &mut transforms,
+                preset,
+                position,
+                tile_size.size,
+            );
+        }
+    }
+}
+
+fn spawn_emitter(
+    entities: &Entities<'_>,
+    emitters: &mut WriteStorage<'_, ParticleEmitterComponent>,
+    transforms: &mut WriteStorage<'_, Transform>,
+    preset: &str,
+    position: glm::IVec2,
+    tile_size: f32,
+) {
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(
+        position.x as f32 * tile_size,
+        position.y as f32 * tile_size,
+        0.8,
+    );
+
+    entities
+        .build_entity()
+        .with(ParticleEmitterComponent::new(preset), emitters)
+        .with(transform, transforms)
+        .build();
+}
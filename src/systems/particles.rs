@@ -0,0 +1,152 @@
+use std::f32::consts::PI;
+
+use amethyst::{
+    core::{
+        math::{Vector2, Vector3},
+        timing::Time,
+        Hidden, Transform,
+    },
+    derive::SystemDesc,
+    ecs::prelude::*,
+    renderer::{palette::Srgba, resources::Tint, transparent::Transparent, SpriteRender},
+};
+use rand::prelude::*;
+
+use crate::components::{ParticleComponent, ParticleEmitterComponent};
+use crate::particles::{EmitterPreset, ParticlePool, ParticlePresets};
+use crate::snake::SnakeSprites;
+
+#[derive(SystemDesc)]
+pub struct ParticleSystem;
+
+impl<'s> System<'s> for ParticleSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, ParticleEmitterComponent>,
+        WriteStorage<'s, ParticleComponent>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, Hidden>,
+        WriteStorage<'s, Transparent>,
+        Write<'s, ParticlePool>,
+        ReadExpect<'s, ParticlePresets>,
+        ReadExpect<'s, SnakeSprites>,
+        Read<'s, Time>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            emitters,
+            mut particles,
+            mut transforms,
+            mut sprites,
+            mut tints,
+            mut hidden,
+            mut transparent,
+            mut pool,
+            presets,
+            snake_sprites,
+            time,
+        ): Self::SystemData,
+    ) {
+        let delta = time.delta_seconds();
+
+        let mut dead = vec![];
+        for (entity, particle, transform) in (&entities, &mut particles, &mut transforms).join() {
+            let preset = match presets.presets.get(&particle.preset) {
+                Some(preset) => preset,
+                None => {
+                    dead.push(entity);
+                    continue;
+                }
+            };
+
+            particle.age += delta;
+            if particle.age >= preset.lifetime {
+                dead.push(entity);
+                continue;
+            }
+
+            transform.prepend_translation_x(particle.velocity.x * delta);
+            transform.prepend_translation_y(particle.velocity.y * delta);
+            particle.velocity *= (1.0 - preset.drag * delta).max(0.0);
+
+            let progress = particle.age / preset.lifetime;
+            let scale = lerp(preset.scale.0, preset.scale.1, progress);
+            transform.set_scale(Vector3::new(scale, scale, 1.0));
+            tints.insert(entity, tint_at(preset, progress)).unwrap();
+        }
+
+        for entity in dead {
+            particles.remove(entity);
+            hidden.insert(entity, Hidden).unwrap();
+            pool.free.push(entity);
+        }
+
+        let mut rng = thread_rng();
+        let mut spent_emitters = vec![];
+        for (entity, emitter) in (&entities, &emitters).join() {
+            spent_emitters.push(entity);
+
+            let preset = match presets.presets.get(&emitter.preset) {
+                Some(preset) => preset,
+                None => continue,
+            };
+            let origin = match transforms.get(entity) {
+                Some(transform) => *transform.translation(),
+                None => continue,
+            };
+            let sprite = match snake_sprites.get_sprite_by_name(&preset.sprite) {
+                Some(sprite) => sprite,
+                None => continue,
+            };
+
+            for _ in 0..preset.count {
+                let particle = pool.free.pop().unwrap_or_else(|| entities.create());
+
+                let angle = rng.gen_range(0.0, 2.0 * PI);
+                let speed = rng.gen_range(preset.speed.0, preset.speed.1);
+                particles
+                    .insert(
+                        particle,
+                        ParticleComponent {
+                            preset: emitter.preset.clone(),
+                            velocity: Vector2::new(angle.cos(), angle.sin()) * speed,
+                            age: 0.0,
+                        },
+                    )
+                    .unwrap();
+
+                let mut transform = Transform::default();
+                transform.set_translation_xyz(origin.x, origin.y, 0.8);
+                transform.set_scale(Vector3::new(preset.scale.0, preset.scale.0, 1.0));
+                transforms.insert(particle, transform).unwrap();
+                sprites.insert(particle, sprite.clone()).unwrap();
+                tints.insert(particle, tint_at(preset, 0.0)).unwrap();
+                transparent.insert(particle, Transparent).unwrap();
+                hidden.remove(particle);
+            }
+        }
+
+        for entity in spent_emitters {
+            entities.delete(entity).unwrap();
+        }
+    }
+}
+
+fn lerp(from: f32, to: f32, progress: f32) -> f32 {
+    from + (to - from) * progress
+}
+
+fn tint_at(preset: &EmitterPreset, progress: f32) -> Tint {
+    let color: Vec<f32> = preset
+        .start_color
+        .iter()
+        .zip(preset.end_color.iter())
+        .map(|(from, to)| lerp(*from, *to, progress))
+        .collect();
+    Tint(Srgba::new(color[0], color[1], color[2], color[3]))
+}
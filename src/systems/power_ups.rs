@@ -0,0 +1,151 @@
+use amethyst::{
+    core::Transform,
+    ecs::prelude::*,
+    renderer::{palette::Srgba, resources::Tint},
+    shrev::EventChannel,
+};
+use rand::prelude::*;
+
+use crate::components::{
+    GamePositionComponent, PowerUpComponent, SnakeComponent, TimedEffectComponent,
+};
+use crate::events::GameEvent;
+use crate::occupancy_grid::{Cell, OccupancyGrid};
+use crate::power_ups::{Effect, PowerUpTable};
+use crate::snake::{GameRng, GameState, SnakeSprites, TickClock};
+
+/// Spawns power-ups on free cells following the `PowerUpTable` and gives their effect to the
+/// snake running into them.
+#[derive(Default)]
+pub struct PowerUpSystem {
+    last_tick: u64,
+}
+
+impl<'s> System<'s> for PowerUpSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, PowerUpComponent>,
+        WriteStorage<'s, GamePositionComponent>,
+        WriteStorage<'s, TimedEffectComponent>,
+        WriteStorage<'s, SnakeComponent>,
+        WriteExpect<'s, OccupancyGrid>,
+        WriteExpect<'s, GameRng>,
+        ReadExpect<'s, PowerUpTable>,
+        ReadExpect<'s, TickClock>,
+        ReadExpect<'s, GameState>,
+        ReadExpect<'s, SnakeSprites>,
+        Read<'s, LazyUpdate>,
+        Write<'s, EventChannel<GameEvent>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut power_ups,
+            mut positions,
+            mut effects,
+            mut snakes,
+            mut grid,
+            mut rng,
+            table,
+            tick_clock,
+            game_state,
+            snake_sprites,
+            updater,
+            mut events,
+        ): Self::SystemData,
+    ) {
+        if *game_state != GameState::Playing || tick_clock.ticks == self.last_tick {
+            return;
+        }
+        self.last_tick = tick_clock.ticks;
+
+        // The moving snakes take over the cells of the power-ups they run into.
+        let collected: Vec<(Entity, usize, Entity)> = (&entities, &power_ups, &positions)
+            .join()
+            .filter_map(
+                |(entity, power_up, position)| match grid.get(&position.position) {
+                    Cell::Snake { snake, .. } => Some((entity, power_up.kind, snake)),
+                    _ => None,
+                },
+            )
+            .collect();
+        let mut on_board = (&power_ups).join().count() - collected.len();
+        for (entity, kind, snake) in collected {
+            entities.delete(entity).unwrap();
+            events.single_write(GameEvent::PowerUpCollected { snake, kind });
+            let power_up = match table.power_ups.get(kind) {
+                Some(power_up) => power_up,
+                None => continue,
+            };
+
+            if let Effect::Shrink { segments } = power_up.effect {
+                if let Some(snake_component) = snakes.get_mut(snake) {
+                    snake_component.shrink(snake, segments, &entities, &mut grid);
+                    events.single_write(GameEvent::Grew {
+                        snake,
+                        length: snake_component.len(),
+                    });
+                }
+                continue;
+            }
+
+            // Collecting a power-up again restarts its effect instead of stacking it.
+            let existing = (&mut effects)
+                .join()
+                .find(|effect| effect.target == snake && effect.effect == power_up.effect);
+            match existing {
+                Some(effect) => {
+                    effect.remaining_ticks = power_up.duration_ticks;
+                    effect.started_at = tick_clock.ticks;
+                }
+                None => {
+                    effects
+                        .insert(
+                            entities.create(),
+                            TimedEffectComponent {
+                                target: snake,
+                                effect: power_up.effect.clone(),
+                                label: power_up.label.clone(),
+                                remaining_ticks: power_up.duration_ticks,
+                                started_at: tick_clock.ticks,
+                            },
+                        )
+                        .unwrap();
+                }
+            }
+        }
+
+        for (kind, power_up) in table.power_ups.iter().enumerate() {
+            if on_board >= table.max_on_board || rng.0.gen::<f32>() >= power_up.probability {
+                continue;
+            }
+            let free_cells: Vec<glm::IVec2> = grid.free_cells().collect();
+            let position = match free_cells.choose(&mut rng.0) {
+                Some(position) => *position,
+                None => break,
+            };
+            let sprite_render = match snake_sprites.get_sprite_by_name(&power_up.sprite) {
+                Some(sprite_render) => sprite_render,
+                None => continue,
+            };
+
+            let entity = entities.create();
+            power_ups.insert(entity, PowerUpComponent { kind }).unwrap();
+            positions
+                .insert(entity, GamePositionComponent::new(position.x, position.y))
+                .unwrap();
+            updater.insert(entity, sprite_render);
+            updater.insert(entity, {
+                let mut transform = Transform::default();
+                transform.set_translation_z(0.5);
+                transform
+            });
+            let [r, g, b, a] = power_up.tint;
+            updater.insert(entity, Tint(Srgba::new(r, g, b, a)));
+            grid.set(&position, Cell::PowerUp(entity));
+            on_board += 1;
+        }
+    }
+}
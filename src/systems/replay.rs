@@ -0,0 +1,56 @@
+use amethyst::{derive::SystemDesc, ecs::prelude::*};
+
+use crate::components::SnakeComponent;
+use crate::replay::{ReplaySession, Turn};
+use crate::snake::TickClock;
+
+/// Records the directions of the snakes when recording a round, or overrides them with the
+/// recorded ones when playing it back.
+#[derive(SystemDesc)]
+pub struct ReplaySystem;
+
+impl<'s> System<'s> for ReplaySystem {
+    type SystemData = (
+        WriteStorage<'s, SnakeComponent>,
+        Option<Write<'s, ReplaySession>>,
+        ReadExpect<'s, TickClock>,
+    );
+
+    fn run(&mut self, (mut snakes, session, tick_clock): Self::SystemData) {
+        let mut session = match session {
+            Some(session) => session,
+            None => return,
+        };
+        let session = &mut *session;
+
+        if session.record_to.is_some() {
+            for snake in (&snakes).join() {
+                if session.directions.get(&snake.player) != Some(&snake.next_direction) {
+                    session
+                        .directions
+                        .insert(snake.player, snake.next_direction.clone());
+                    session.replay.turns.push(Turn {
+                        tick: tick_clock.ticks,
+                        player: snake.player,
+                        direction: snake.next_direction.clone(),
+                    });
+                }
+            }
+        } else {
+            while let Some(turn) = session.replay.turns.get(session.next_turn) {
+                if turn.tick > tick_clock.ticks {
+                    break;
+                }
+                session
+                    .directions
+                    .insert(turn.player, turn.direction.clone());
+                session.next_turn += 1;
+            }
+            for snake in (&mut snakes).join() {
+                if let Some(direction) = session.directions.get(&snake.player) {
+                    snake.next_direction = direction.clone();
+                }
+            }
+        }
+    }
+}
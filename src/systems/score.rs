@@ -0,0 +1,52 @@
+use amethyst::{
+    derive::SystemDesc,
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::components::{SnakeComponent, TimedEffectComponent};
+use crate::events::GameEvent;
+use crate::fruits::FruitTable;
+
+/// Adds the points of the fruits eaten to the score of the snakes, multiplied by their
+/// `ScoreMultiplier` effects.
+#[derive(SystemDesc)]
+#[system_desc(name(ScoreSystemDesc))]
+pub struct ScoreSystem {
+    #[system_desc(event_channel_reader)]
+    reader_id: ReaderId<GameEvent>,
+}
+
+impl ScoreSystem {
+    pub fn new(reader_id: ReaderId<GameEvent>) -> Self {
+        ScoreSystem { reader_id }
+    }
+}
+
+impl<'s> System<'s> for ScoreSystem {
+    type SystemData = (
+        Read<'s, EventChannel<GameEvent>>,
+        WriteStorage<'s, SnakeComponent>,
+        ReadStorage<'s, TimedEffectComponent>,
+        ReadExpect<'s, FruitTable>,
+    );
+
+    fn run(&mut self, (events, mut snakes, effects, fruit_table): Self::SystemData) {
+        for event in events.read(&mut self.reader_id) {
+            if let GameEvent::AppleEaten { snake, fruit, .. } = event {
+                let points = match fruit_table.fruits.get(*fruit) {
+                    Some(fruit) => fruit.points,
+                    None => continue,
+                };
+                if let Some(snake_component) = snakes.get_mut(*snake) {
+                    snake_component.score += points
+                        * (&effects)
+                            .join()
+                            .filter(|effect| effect.target == *snake)
+                            .map(|effect| effect.effect.score_factor())
+                            .product::<u32>();
+                }
+            }
+        }
+    }
+}
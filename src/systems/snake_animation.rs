@@ -0,0 +1,105 @@
+use amethyst::{
+    derive::SystemDesc,
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::animation::{BODY_GULP, DEATH_FLASH, HEAD_MOUTH_OPEN};
+use crate::components::{SnakeComponent, SpriteAnimationComponent};
+use crate::events::GameEvent;
+use crate::occupancy_grid::{Cell, OccupancyGrid};
+use crate::ruleset::Ruleset;
+use crate::snake::GameState;
+
+#[derive(SystemDesc)]
+#[system_desc(name(SnakeAnimationSystemDesc))]
+pub struct SnakeAnimationSystem {
+    #[system_desc(event_channel_reader)]
+    reader_id: ReaderId<GameEvent>,
+}
+
+impl SnakeAnimationSystem {
+    pub fn new(reader_id: ReaderId<GameEvent>) -> Self {
+        SnakeAnimationSystem { reader_id }
+    }
+}
+
+impl<'s> System<'s> for SnakeAnimationSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, SnakeComponent>,
+        WriteStorage<'s, SpriteAnimationComponent>,
+        ReadExpect<'s, OccupancyGrid>,
+        Read<'s, EventChannel<GameEvent>>,
+        ReadExpect<'s, GameState>,
+        ReadExpect<'s, Ruleset>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, snakes, mut animations, grid, events, game_state, ruleset): Self::SystemData,
+    ) {
+        let eaters: Vec<Entity> = events
+            .read(&mut self.reader_id)
+            .filter_map(|event| match event {
+                GameEvent::AppleEaten { snake, .. } => Some(*snake),
+                _ => None,
+            })
+            .collect();
+
+        for (entity, snake) in (&entities, &snakes).join() {
+            if *game_state == GameState::GameOver {
+                for segment in &snake.segments {
+                    if !is_playing(&animations, *segment, DEATH_FLASH) {
+                        animations
+                            .insert(*segment, SpriteAnimationComponent::new(DEATH_FLASH))
+                            .unwrap();
+                    }
+                }
+                continue;
+            }
+
+            let head = snake.segments[0];
+            let ahead = ruleset.next_cell(&grid, snake.head(), &snake.next_direction);
+            let apple_ahead = matches!(grid.get(&ahead), Cell::Apple(_));
+
+            if eaters.contains(&entity) {
+                animations
+                    .insert(head, SpriteAnimationComponent::new(BODY_GULP))
+                    .unwrap();
+            } else if apple_ahead && !animations.contains(head) {
+                animations
+                    .insert(head, SpriteAnimationComponent::new(HEAD_MOUTH_OPEN))
+                    .unwrap();
+            } else if !apple_ahead && is_playing(&animations, head, HEAD_MOUTH_OPEN) {
+                animations.remove(head);
+            }
+
+            // The previous head keeps its entity when the snake moves, so the mouth has to be
+            // closed behind it.
+            if let Some(neck) = snake.segments.get(1) {
+                if is_playing(&animations, *neck, HEAD_MOUTH_OPEN) {
+                    animations.remove(*neck);
+                }
+            }
+
+            // The gulp stays on the cell where the apple was eaten and travels down the body
+            // until the tail reaches it.
+            if let Some(tail) = snake.segments.back() {
+                if snake.len() > 1 && is_playing(&animations, *tail, BODY_GULP) {
+                    animations.remove(*tail);
+                }
+            }
+        }
+    }
+}
+
+fn is_playing(
+    animations: &WriteStorage<'_, SpriteAnimationComponent>,
+    entity: Entity,
+    clip: &str,
+) -> bool {
+    animations
+        .get(entity)
+        .map_or(false, |animation| animation.is_playing(clip))
+}
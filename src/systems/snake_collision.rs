@@ -1,42 +1,119 @@
-use crate::components::{GamePositionComponent, SnakeHeadComponent, SnakePartComponent};
-use crate::snake::GameState::GameOver;
-use crate::snake::{GameState, ARENA_HEIGHT, ARENA_WIDTH};
-use amethyst::{
-    core::Transform, derive::SystemDesc, ecs::prelude::*, prelude::*, renderer::SpriteRender,
-};
+use crate::components::{GamePositionComponent, SnakeComponent, TimedEffectComponent};
+use crate::events::GameEvent;
+use crate::occupancy_grid::{Cell, OccupancyGrid};
+use crate::power_ups::Effect;
+use crate::ruleset::{Ruleset, SelfCollision};
+use crate::snake::{GameRng, GameState};
+use amethyst::{ecs::prelude::*, shrev::EventChannel};
 
 pub struct SnakeCollisionSystem;
 
 impl<'s> System<'s> for SnakeCollisionSystem {
     type SystemData = (
-        ReadStorage<'s, SnakePartComponent>,
-        ReadStorage<'s, GamePositionComponent>,
-        ReadStorage<'s, SnakeHeadComponent>,
+        Entities<'s>,
+        WriteStorage<'s, SnakeComponent>,
+        WriteStorage<'s, GamePositionComponent>,
+        ReadStorage<'s, TimedEffectComponent>,
+        WriteExpect<'s, OccupancyGrid>,
         WriteExpect<'s, GameState>,
+        WriteExpect<'s, GameRng>,
+        ReadExpect<'s, Ruleset>,
+        Write<'s, EventChannel<GameEvent>>,
     );
 
-    fn run(&mut self, (snake_parts, positions, snake_heads, mut game_state): Self::SystemData) {
-        let mut collision = false;
-        for (snake_part, position, _) in (&snake_parts, &positions, &snake_heads).join() {
-            for (other_snake_part, other_position) in (&snake_parts, &positions).join() {
-                if position.position == other_position.position
-                    && snake_part.next_snake_part != other_snake_part.next_snake_part
-                {
-                    collision = true;
-                }
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut snakes,
+            mut positions,
+            effects,
+            mut grid,
+            mut game_state,
+            mut rng,
+            ruleset,
+            mut events,
+        ): Self::SystemData,
+    ) {
+        if *game_state != GameState::Playing {
+            return;
+        }
+
+        let ghosts: Vec<Entity> = (&effects)
+            .join()
+            .filter(|effect| effect.effect == Effect::Ghost)
+            .map(|effect| effect.target)
+            .collect();
+
+        // Every snake is checked before any of them is cut or respawned, so that the outcome does
+        // not depend on the order in which they are processed.
+        let mut bitten = vec![];
+        let mut dead = vec![];
+        for (entity, snake) in (&entities, &snakes).join() {
+            let head_cell = Cell::Snake {
+                snake: entity,
+                segment: snake.head_serial,
+            };
+
+            // The movement system never overwrites walls or bodies, nor a cell reached by several
+            // heads, so if the head does not own its cell it ran into something.
+            let cell = grid.get(&snake.head());
+            if cell == head_cell {
+                continue;
             }
-            let pos = position.position;
-            if !collision
-                && (pos.x <= 0
-                    || pos.x >= ARENA_WIDTH - 1
-                    || pos.y <= 0
-                    || pos.y >= ARENA_HEIGHT - 1)
+            if let Cell::Snake {
+                snake: owner,
+                segment,
+            } = cell
             {
-                collision = true;
+                if owner == entity {
+                    if ghosts.contains(&entity)
+                        || ruleset.self_collision == SelfCollision::PassThrough
+                    {
+                        continue;
+                    }
+                    if ruleset.self_collision == SelfCollision::CutTail {
+                        bitten.push((entity, segment));
+                        continue;
+                    }
+                }
             }
+            dead.push(entity);
+        }
 
-            if collision {
-                *game_state = GameState::GameOver;
+        for (entity, segment) in bitten {
+            if let Some(snake) = snakes.get_mut(entity) {
+                let bitten = snake.len() - snake.segment_index(segment);
+                snake.shrink(entity, bitten, &entities, &mut grid);
+                grid.set(
+                    &snake.head(),
+                    Cell::Snake {
+                        snake: entity,
+                        segment: snake.head_serial,
+                    },
+                );
+                events.single_write(GameEvent::Grew {
+                    snake: entity,
+                    length: snake.len(),
+                });
+            }
+        }
+
+        // The dead snakes leave the board before any of them is put back on it.
+        for &entity in &dead {
+            if let Some(snake) = snakes.get(entity) {
+                events.single_write(GameEvent::Died {
+                    snake: entity,
+                    position: snake.head(),
+                });
+                snake.release_cells(entity, &mut grid);
+            }
+        }
+        for entity in dead {
+            if let Some(snake) = snakes.get_mut(entity) {
+                if !snake.lose_life(entity, &entities, &mut positions, &mut grid, &mut rng) {
+                    *game_state = GameState::GameOver;
+                }
             }
         }
     }
@@ -0,0 +1,47 @@
+use amethyst::{core::Transform, derive::SystemDesc, ecs::prelude::*};
+
+use crate::components::SnakeComponent;
+use crate::occupancy_grid::OccupancyGrid;
+use crate::settings::{MovementRendering, Settings};
+use crate::snake::{TickClock, TileSize};
+
+#[derive(SystemDesc)]
+pub struct SnakeInterpolationSystem;
+
+impl<'s> System<'s> for SnakeInterpolationSystem {
+    type SystemData = (
+        WriteStorage<'s, Transform>,
+        ReadStorage<'s, SnakeComponent>,
+        ReadExpect<'s, TickClock>,
+        ReadExpect<'s, Settings>,
+        ReadExpect<'s, TileSize>,
+        ReadExpect<'s, OccupancyGrid>,
+    );
+
+    fn run(
+        &mut self,
+        (mut transforms, snakes, tick_clock, settings, tile_size, grid): Self::SystemData,
+    ) {
+        if settings.movement_rendering != MovementRendering::Interpolated {
+            return;
+        }
+
+        let alpha = tick_clock.alpha();
+        for snake in (&snakes).join() {
+            // Every segment moves by exactly one cell per tick along the body, so following the
+            // path around turns only needs a straight interpolation between two adjacent cells.
+            for (index, segment) in snake.segments.iter().enumerate() {
+                let to = snake.body[index];
+                // Across a wrapped edge the segment comes in from outside of the board.
+                let from = grid.unwrap_near(&snake.previous_position(index), &to);
+
+                if let Some(transform) = transforms.get_mut(*segment) {
+                    let x = from.x as f32 + (to.x - from.x) as f32 * alpha;
+                    let y = from.y as f32 + (to.y - from.y) as f32 * alpha;
+                    let z = transform.translation().z;
+                    transform.set_translation_xyz(x * tile_size.size, y * tile_size.size, z);
+                }
+            }
+        }
+    }
+}
@@ -1,10 +1,9 @@
 use std::f32::consts::PI;
 
-use amethyst::{
-    core::Transform, derive::SystemDesc, ecs::prelude::*, prelude::*, renderer::SpriteRender,
-};
+use amethyst::{core::Transform, derive::SystemDesc, ecs::prelude::*, renderer::SpriteRender};
 
-use crate::components::{GamePositionComponent, SnakeHeadComponent, SnakePartComponent};
+use crate::components::SnakeComponent;
+use crate::occupancy_grid::OccupancyGrid;
 use crate::snake::{Direction, SnakeSprites, SnakeSpritesKeys};
 
 #[derive(SystemDesc)]
@@ -12,82 +11,56 @@ pub struct SnakeRenderSystem;
 
 impl<'s> System<'s> for SnakeRenderSystem {
     type SystemData = (
-        Entities<'s>,
         WriteStorage<'s, SpriteRender>,
         WriteStorage<'s, Transform>,
-        ReadStorage<'s, GamePositionComponent>,
-        ReadStorage<'s, SnakePartComponent>,
-        ReadStorage<'s, SnakeHeadComponent>,
+        ReadStorage<'s, SnakeComponent>,
         ReadExpect<'s, SnakeSprites>,
+        ReadExpect<'s, OccupancyGrid>,
     );
 
-    fn run(
-        &mut self,
-        (entities, mut sprites, mut transforms, game_positions, parts, heads, sprite_asset): Self::SystemData,
-    ) {
-        let mut current_position = &glm::vec2(0, 0);
-        let mut next_position = &glm::vec2(0, 0);
-        let mut previous_position = &glm::vec2(0, 0);
-        let mut head_entity = None;
-        for (_, position, entity) in (&heads, &game_positions, &entities).join() {
-            current_position = &position.position;
-            previous_position = &current_position;
-            head_entity = Some(entity);
-        }
-
-        for (_, part) in (&heads, &parts).join() {
-            let mut next_to_check = part.next_snake_part;
-            let mut last_entity = None;
-
-            next_position = &game_positions.get(next_to_check.unwrap()).unwrap().position;
-            let angle = get_head_angle(current_position, next_position);
-
-            let mut head_transform = transforms.get_mut(head_entity.unwrap()).unwrap();
-            head_transform.set_rotation_2d(angle);
-
-            loop {
-                if let Some(next_body_part_entity) = next_to_check {
-                    current_position = next_position;
-                    next_to_check = parts.get(next_body_part_entity).unwrap().next_snake_part;
-                    if next_to_check.is_some() {
-                        next_position =
-                            &game_positions.get(next_to_check.unwrap()).unwrap().position;
+    fn run(&mut self, (mut sprites, mut transforms, snakes, sprite_asset, grid): Self::SystemData) {
+        for snake in (&snakes).join() {
+            let body = &snake.body;
+            let last_index = body.len() - 1;
+
+            for (index, entity) in snake.segments.iter().enumerate() {
+                let current_position = &body[index];
+                // The neighbours of a segment lying across a wrapped edge are moved next to it.
+                let neighbour = |index: usize| grid.unwrap_near(&body[index], current_position);
+                let (sprite_key, angle) = if index == 0 {
+                    if last_index == 0 {
+                        continue;
+                    }
+                    (
+                        SnakeSpritesKeys::SnakeHead,
+                        Some(get_head_angle(current_position, &neighbour(1))),
+                    )
+                } else if index == last_index {
+                    (
+                        SnakeSpritesKeys::SnakeTail,
+                        Some(get_tail_angle(current_position, &neighbour(index - 1))),
+                    )
+                } else {
+                    let previous_position = &neighbour(index - 1);
+                    let next_position = &neighbour(index + 1);
+                    if are_axis_aligned(previous_position, next_position) {
+                        (
+                            SnakeSpritesKeys::SnakeBody,
+                            Some(get_straight_angle(next_position, previous_position)),
+                        )
                     } else {
-                        last_entity = Some(next_body_part_entity);
-                        break;
+                        (
+                            SnakeSpritesKeys::SnakeTurn,
+                            get_turn_angle(current_position, next_position, previous_position),
+                        )
                     }
+                };
 
-                    if let Some(sprite) = sprites.get_mut(next_body_part_entity) {
-                        if are_axis_aligned(previous_position, next_position) {
-                            let angle = get_straight_angle(next_position, previous_position);
-
-                            let mut transform = transforms.get_mut(next_body_part_entity).unwrap();
-                            transform.set_rotation_2d(angle);
-
-                            *sprite = sprite_asset.get_sprite_clone(SnakeSpritesKeys::SnakeBody);
-                        } else {
-                            let angle =
-                                get_turn_angle(current_position, next_position, previous_position);
-                            if angle.is_none() {
-                                break;
-                            }
-                            let mut transform = transforms.get_mut(next_body_part_entity).unwrap();
-                            transform.set_rotation_2d(angle.unwrap());
-                            *sprite = sprite_asset.get_sprite_clone(SnakeSpritesKeys::SnakeTurn);
-                        }
-                    }
-                    previous_position = &current_position;
+                if let Some(sprite) = sprites.get_mut(*entity) {
+                    *sprite = sprite_asset.get_skin_sprite_clone(snake.player, sprite_key);
                 }
-            }
-            if let Some(entity) = last_entity {
-                current_position = next_position;
-                if let Some(sprite) = sprites.get_mut(entity) {
-                    let angle = get_tail_angle(current_position, previous_position);
-
-                    let mut tail_transform = transforms.get_mut(entity).unwrap();
-                    tail_transform.set_rotation_2d(angle);
-
-                    *sprite = sprite_asset.get_sprite_clone(SnakeSpritesKeys::SnakeTail);
+                if let (Some(angle), Some(transform)) = (angle, transforms.get_mut(*entity)) {
+                    transform.set_rotation_2d(angle);
                 }
             }
         }
@@ -0,0 +1,55 @@
+use amethyst::{
+    assets::AssetStorage,
+    audio::{output::Output, Source},
+    derive::SystemDesc,
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::audio::{Sounds, DEATH, EAT, LEVEL_UP, TURN};
+use crate::events::GameEvent;
+use crate::settings::Settings;
+
+/// Plays the sound of the `GameEvent`s published on this frame, each sound at most once.
+#[derive(SystemDesc)]
+#[system_desc(name(SoundEffectsSystemDesc))]
+pub struct SoundEffectsSystem {
+    #[system_desc(event_channel_reader)]
+    reader_id: ReaderId<GameEvent>,
+}
+
+impl SoundEffectsSystem {
+    pub fn new(reader_id: ReaderId<GameEvent>) -> Self {
+        SoundEffectsSystem { reader_id }
+    }
+}
+
+impl<'s> System<'s> for SoundEffectsSystem {
+    type SystemData = (
+        Option<Read<'s, Output>>,
+        Read<'s, AssetStorage<Source>>,
+        ReadExpect<'s, Sounds>,
+        ReadExpect<'s, Settings>,
+        Read<'s, EventChannel<GameEvent>>,
+    );
+
+    fn run(&mut self, (output, storage, sounds, settings, events): Self::SystemData) {
+        let mut names = vec![];
+        for event in events.read(&mut self.reader_id) {
+            let name = match event {
+                GameEvent::Turned { .. } => TURN,
+                GameEvent::AppleEaten { .. } => EAT,
+                GameEvent::Died { .. } => DEATH,
+                GameEvent::LevelCompleted => LEVEL_UP,
+                _ => continue,
+            };
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        for name in names {
+            sounds.play(name, &storage, output.as_deref(), &settings.volume);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use amethyst::{
+    derive::SystemDesc,
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::events::GameEvent;
+use crate::fruits::FruitTable;
+use crate::ruleset::Ruleset;
+use crate::snake::TickClock;
+
+/// Shortens the base time between two moves for every fruit eaten that makes the snake grow,
+/// following the speed curve of the `Ruleset`.
+#[derive(SystemDesc)]
+#[system_desc(name(SpeedCurveSystemDesc))]
+pub struct SpeedCurveSystem {
+    #[system_desc(event_channel_reader)]
+    reader_id: ReaderId<GameEvent>,
+}
+
+impl SpeedCurveSystem {
+    pub fn new(reader_id: ReaderId<GameEvent>) -> Self {
+        SpeedCurveSystem { reader_id }
+    }
+}
+
+impl<'s> System<'s> for SpeedCurveSystem {
+    type SystemData = (
+        Read<'s, EventChannel<GameEvent>>,
+        WriteExpect<'s, TickClock>,
+        ReadExpect<'s, Ruleset>,
+        ReadExpect<'s, FruitTable>,
+    );
+
+    fn run(&mut self, (events, mut tick_clock, ruleset, fruit_table): Self::SystemData) {
+        let speed = &ruleset.speed;
+        for event in events.read(&mut self.reader_id) {
+            if let GameEvent::AppleEaten { fruit, .. } = event {
+                let grows = fruit_table
+                    .fruits
+                    .get(*fruit)
+                    .map_or(false, |fruit| ruleset.growth(fruit) > 0);
+                if !grows {
+                    continue;
+                }
+                let min_interval = Duration::from_millis(speed.min_interval_ms);
+                if tick_clock.base_interval > min_interval {
+                    tick_clock.base_interval = tick_clock
+                        .base_interval
+                        .checked_sub(Duration::from_millis(speed.step_ms))
+                        .unwrap_or_default()
+                        .max(min_interval);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use amethyst::{
+    core::{math::Vector3, timing::Time, Transform},
+    derive::SystemDesc,
+    ecs::prelude::*,
+    renderer::{palette::Srgba, resources::Tint, SpriteRender},
+};
+
+use crate::animation::SpriteMetadata;
+use crate::components::SpriteAnimationComponent;
+use crate::snake::SnakeSprites;
+
+#[derive(SystemDesc)]
+pub struct SpriteAnimationSystem;
+
+impl<'s> System<'s> for SpriteAnimationSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, SpriteAnimationComponent>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Tint>,
+        ReadExpect<'s, SpriteMetadata>,
+        ReadExpect<'s, SnakeSprites>,
+        Read<'s, Time>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut animations,
+            mut sprites,
+            mut transforms,
+            mut tints,
+            metadata,
+            snake_sprites,
+            time,
+        ): Self::SystemData,
+    ) {
+        let mut finished = vec![];
+
+        for (entity, animation) in (&entities, &mut animations).join() {
+            animation.elapsed += time.delta_seconds();
+
+            let frame = match metadata
+                .animations
+                .get(&animation.clip)
+                .and_then(|clip| clip.frame_at(animation.elapsed))
+            {
+                Some(frame) => frame,
+                None => {
+                    finished.push(entity);
+                    continue;
+                }
+            };
+
+            if let (Some(name), Some(sprite)) = (&frame.sprite, sprites.get_mut(entity)) {
+                // Themes may not have every sprite of the default one, keep the current sprite then.
+                if let Some(sprite_number) = snake_sprites.sprite_number(&sprite.sprite_sheet, name)
+                {
+                    sprite.sprite_number = sprite_number;
+                }
+            }
+            if let Some(transform) = transforms.get_mut(entity) {
+                transform.set_scale(Vector3::new(frame.scale.0, frame.scale.1, 1.0));
+            }
+            match frame.tint {
+                Some([r, g, b, a]) => {
+                    tints.insert(entity, Tint(Srgba::new(r, g, b, a))).unwrap();
+                }
+                None => {
+                    tints.remove(entity);
+                }
+            }
+        }
+
+        for entity in finished {
+            animations.remove(entity);
+            tints.remove(entity);
+            if let Some(transform) = transforms.get_mut(entity) {
+                transform.set_scale(Vector3::new(1.0, 1.0, 1.0));
+            }
+        }
+    }
+}
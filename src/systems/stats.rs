@@ -0,0 +1,41 @@
+use amethyst::{
+    derive::SystemDesc,
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::events::GameEvent;
+use crate::stats::Stats;
+
+/// Counts the `GameEvent`s of every round into the `Stats`.
+#[derive(SystemDesc)]
+#[system_desc(name(StatsSystemDesc))]
+pub struct StatsSystem {
+    #[system_desc(event_channel_reader)]
+    reader_id: ReaderId<GameEvent>,
+}
+
+impl StatsSystem {
+    pub fn new(reader_id: ReaderId<GameEvent>) -> Self {
+        StatsSystem { reader_id }
+    }
+}
+
+impl<'s> System<'s> for StatsSystem {
+    type SystemData = (Read<'s, EventChannel<GameEvent>>, Write<'s, Stats>);
+
+    fn run(&mut self, (events, mut stats): Self::SystemData) {
+        for event in events.read(&mut self.reader_id) {
+            match event {
+                GameEvent::AppleEaten { .. } => stats.apples_eaten += 1,
+                GameEvent::Turned { .. } => stats.turns += 1,
+                GameEvent::Grew { length, .. } => {
+                    stats.longest_snake = stats.longest_snake.max(*length)
+                }
+                GameEvent::Died { .. } => stats.deaths += 1,
+                GameEvent::PowerUpCollected { .. } => stats.power_ups += 1,
+                GameEvent::LevelCompleted => stats.levels_completed += 1,
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use amethyst::ecs::prelude::*;
+
+use crate::components::{
+    AppleComponent, GamePositionComponent, SnakeComponent, TimedEffectComponent,
+};
+use crate::occupancy_grid::{Cell, OccupancyGrid};
+use crate::power_ups::Effect;
+use crate::snake::{GameState, TickClock};
+
+/// Applies the `TimedEffectComponent`s that act on the whole game, the speed changes and the
+/// apple magnet, and removes the effects once they run out.
+#[derive(Default)]
+pub struct TimedEffectSystem {
+    last_tick: u64,
+}
+
+impl<'s> System<'s> for TimedEffectSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, TimedEffectComponent>,
+        ReadStorage<'s, SnakeComponent>,
+        ReadStorage<'s, AppleComponent>,
+        WriteStorage<'s, GamePositionComponent>,
+        WriteExpect<'s, OccupancyGrid>,
+        WriteExpect<'s, TickClock>,
+        ReadExpect<'s, GameState>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut effects,
+            snakes,
+            apples,
+            mut positions,
+            mut grid,
+            mut tick_clock,
+            game_state,
+        ): Self::SystemData,
+    ) {
+        let interval_factor: f32 = (&effects)
+            .join()
+            .map(|effect| effect.effect.interval_factor())
+            .product();
+        tick_clock.interval = tick_clock.base_interval.mul_f32(interval_factor.max(0.1));
+
+        if *game_state != GameState::Playing || tick_clock.ticks == self.last_tick {
+            return;
+        }
+        self.last_tick = tick_clock.ticks;
+
+        for (entity, effect) in (&entities, &mut effects).join() {
+            if effect.effect == Effect::Magnet {
+                if let Some(snake) = snakes.get(effect.target) {
+                    attract_apples(&entities, &apples, &mut positions, &mut grid, snake.head());
+                }
+            }
+
+            // The power-ups run before, so an effect collected on this tick has not acted yet.
+            if effect.started_at == tick_clock.ticks {
+                continue;
+            }
+            effect.remaining_ticks = effect.remaining_ticks.saturating_sub(1);
+            if effect.remaining_ticks == 0 {
+                entities.delete(entity).unwrap();
+            }
+        }
+    }
+}
+
+/// Moves every apple one cell towards `head`, along the axis it is the farthest on, if that cell
+/// is empty.
+fn attract_apples(
+    entities: &Entities<'_>,
+    apples: &ReadStorage<'_, AppleComponent>,
+    positions: &mut WriteStorage<'_, GamePositionComponent>,
+    grid: &mut OccupancyGrid,
+    head: glm::IVec2,
+) {
+    for (apple, _, position) in (entities, apples, &mut *positions).join() {
+        let offset = head - position.position;
+        let step = if offset.x.abs() > offset.y.abs() {
+            glm::vec2(offset.x.signum(), 0)
+        } else {
+            glm::vec2(0, offset.y.signum())
+        };
+        let target = position.position + step;
+        if step != glm::vec2(0, 0) && grid.is_free(&target) {
+            if grid.get(&position.position) == Cell::Apple(apple) {
+                grid.clear(&position.position);
+            }
+            position.position = target;
+            grid.set(&target, Cell::Apple(apple));
+        }
+    }
+}
@@ -1,8 +1,7 @@
-use amethyst::{
-    core::Transform, derive::SystemDesc, ecs::prelude::*, prelude::*, renderer::SpriteRender,
-};
+use amethyst::{core::Transform, derive::SystemDesc, ecs::prelude::*};
 
 use crate::components::GamePositionComponent;
+use crate::snake::TileSize;
 
 #[derive(SystemDesc)]
 pub struct TransformPositionsSystem;
@@ -11,11 +10,13 @@ impl<'s> System<'s> for TransformPositionsSystem {
     type SystemData = (
         WriteStorage<'s, Transform>,
         ReadStorage<'s, GamePositionComponent>,
+        ReadExpect<'s, TileSize>,
     );
 
-    fn run(&mut self, (mut transforms, game_positions): Self::SystemData) {
+    fn run(&mut self, (mut transforms, game_positions, tile_size): Self::SystemData) {
         for (transform, game_position) in (&mut transforms, &game_positions).join() {
-            *transform = game_position.to_transform_with_z(transform.translation().z);
+            *transform =
+                game_position.to_transform_with_z(tile_size.size, transform.translation().z);
         }
     }
 }
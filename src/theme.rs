@@ -0,0 +1,222 @@
+use std::path::Path;
+
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    config::{Config, ConfigError},
+    prelude::*,
+    ui::{get_default_font, FontAsset, FontHandle, TtfFormat},
+    Error, Result,
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::animation::SpriteMetadata;
+use crate::fruits::FruitTable;
+use crate::particles::ParticlePresets;
+use crate::power_ups::PowerUpTable;
+use crate::settings::{Settings, UserFiles};
+use crate::snake::SnakeSpritesKeys;
+use crate::sprite_sheet::SpriteSheetDefinition;
+
+pub const DEFAULT_THEME: &str = "default";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ThemeColors {
+    pub background: [f32; 4],
+    pub text: [f32; 4],
+    pub highlight: [f32; 4],
+}
+
+impl Default for ThemeColors {
+    fn default() -> Self {
+        ThemeColors {
+            background: [0.3, 0.3, 0.3, 1.0],
+            text: [1.0, 1.0, 1.0, 1.0],
+            highlight: [1.0, 0.85, 0.2, 1.0],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ThemeDefinition {
+    #[serde(default)]
+    pub font: Option<String>,
+    #[serde(default)]
+    pub colors: Option<ThemeColors>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub name: String,
+    pub font: Option<String>,
+    pub colors: ThemeColors,
+    pub texture: String,
+    pub sprite_sheet: SpriteSheetDefinition,
+    pub metadata: SpriteMetadata,
+}
+
+impl Theme {
+    /// Loads `assets/themes/<name>`. Anything the folder does not provide is taken from
+    /// `fallback`; without a fallback the sprite sheet, texture and metadata are required.
+    pub fn load(assets: &Path, name: &str, fallback: Option<&Theme>) -> Result<Theme, ConfigError> {
+        let folder = format!("themes/{}", name);
+        let file = |file_name: &str| format!("{}/{}", folder, file_name);
+        let exists = |file_name: &str| assets.join(file(file_name)).exists();
+
+        if let Some(fallback) = fallback {
+            if !assets.join(&folder).is_dir() {
+                warn!("Theme {} not found, using {}", name, fallback.name);
+                return Ok(fallback.clone());
+            }
+        }
+
+        let definition = if fallback.is_none() || exists("theme.ron") {
+            ThemeDefinition::load(assets.join(file("theme.ron")))?
+        } else {
+            ThemeDefinition::default()
+        };
+
+        let metadata = match fallback {
+            Some(fallback) if !exists("snake_metadata.ron") => fallback.metadata.clone(),
+            Some(fallback) => {
+                let mut metadata = fallback.metadata.clone();
+                let theme_metadata = SpriteMetadata::load(assets.join(file("snake_metadata.ron")))?;
+                metadata.animations.extend(theme_metadata.animations);
+                metadata
+            }
+            None => SpriteMetadata::load(assets.join(file("snake_metadata.ron")))?,
+        };
+
+        let texture = match fallback {
+            Some(fallback) if !exists("snake.png") => fallback.texture.clone(),
+            _ => file("snake.png"),
+        };
+
+        let sprite_sheet = match fallback {
+            Some(fallback) if !exists("snake.ron") => fallback.sprite_sheet.clone(),
+            _ => SpriteSheetDefinition::load(assets.join(file("snake.ron")))?,
+        };
+
+        Ok(Theme {
+            name: name.to_string(),
+            font: definition
+                .font
+                .map(|font| file(&font))
+                .or_else(|| fallback.and_then(|fallback| fallback.font.clone())),
+            colors: definition
+                .colors
+                .or_else(|| fallback.map(|fallback| fallback.colors.clone()))
+                .unwrap_or_default(),
+            texture,
+            sprite_sheet,
+            metadata,
+        })
+    }
+}
+
+pub struct Themes {
+    pub default: Theme,
+    pub selected: Theme,
+    pub skins: Vec<Theme>,
+}
+
+impl Themes {
+    pub fn load(assets: &Path, settings: &Settings) -> Result<Themes, ConfigError> {
+        let default = Theme::load(assets, DEFAULT_THEME, None)?;
+        let selected = Theme::load(assets, &settings.theme, Some(&default))?;
+        let skins = settings
+            .players
+            .iter()
+            .map(|player| match &player.skin {
+                Some(skin) => Theme::load(assets, skin, Some(&default)),
+                None => Ok(selected.clone()),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Themes {
+            default,
+            selected,
+            skins,
+        })
+    }
+
+    /// Checks that the sprites the game draws by name exist. The default theme must have all of
+    /// them since every other theme falls back to it for the sprites it does not provide.
+    pub fn validate(
+        &self,
+        particles: &ParticlePresets,
+        power_ups: &PowerUpTable,
+        fruits: &FruitTable,
+    ) -> Result<()> {
+        let named_sprites = particles
+            .presets
+            .values()
+            .map(|preset| preset.sprite.as_str())
+            .chain(
+                power_ups
+                    .power_ups
+                    .iter()
+                    .map(|power_up| power_up.sprite.as_str()),
+            )
+            .chain(fruits.fruits.iter().map(|fruit| fruit.sprite.as_str()));
+        let mut missing = self
+            .default
+            .sprite_sheet
+            .missing_sprites(SnakeSpritesKeys::ALL.iter().map(|key| key.name()));
+
+        let selected_sprites = self.selected.sprite_sheet.sprite_numbers();
+        missing.extend(
+            self.default
+                .sprite_sheet
+                .missing_sprites(self.selected.metadata.sprite_names().chain(named_sprites))
+                .into_iter()
+                .filter(|name| !selected_sprites.contains_key(name)),
+        );
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::from_string(format!(
+                "The sprite sheet of the {} theme is missing these sprites: {}",
+                self.default.name,
+                missing.join(", ")
+            )))
+        }
+    }
+}
+
+/// Reloads the themes named by the current `Settings`, keeping the current ones when the new ones
+/// are invalid.
+pub fn reload_themes(world: &mut World) -> Result<()> {
+    let themes = load_valid_themes(world, &world.read_resource::<Settings>())?;
+    insert_themes(world, themes);
+    Ok(())
+}
+
+/// Loads the themes named by `settings` and checks them against the current presets and tables,
+/// without changing the world.
+pub fn load_valid_themes(world: &World, settings: &Settings) -> Result<Themes> {
+    let themes = Themes::load(&world.read_resource::<UserFiles>().assets, settings)?;
+    themes.validate(
+        &world.read_resource::<ParticlePresets>(),
+        &world.read_resource::<PowerUpTable>(),
+        &world.read_resource::<FruitTable>(),
+    )?;
+    Ok(themes)
+}
+
+/// Makes `themes` the current themes, along with the sprite metadata of the selected one.
+pub fn insert_themes(world: &mut World, themes: Themes) {
+    world.insert(themes.selected.metadata.clone());
+    world.insert(themes);
+}
+
+pub fn load_font(world: &World) -> FontHandle {
+    let themes = world.read_resource::<Themes>();
+    let loader = world.read_resource::<Loader>();
+    let font_storage = world.read_resource::<AssetStorage<FontAsset>>();
+    match &themes.selected.font {
+        Some(font) => loader.load(font.as_str(), TtfFormat, (), &font_storage),
+        None => get_default_font(&loader, &font_storage),
+    }
+}
@@ -0,0 +1,68 @@
+use amethyst::{
+    core::*,
+    prelude::*,
+    ui::{Anchor, UiText, UiTransform},
+    GameData, SimpleState, SimpleTrans, StateData,
+};
+use log::info;
+
+use crate::audio::{play_effect, Music, MusicMode, MENU};
+use crate::snake::SnakeGame;
+use crate::stats::Stats;
+use crate::theme::{load_font, Themes};
+
+pub struct VictoryState;
+
+impl SimpleState for VictoryState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        data.world.delete_all();
+
+        data.world
+            .write_resource::<Music>()
+            .set_mode(MusicMode::Victory);
+        play_effect(data.world, MENU);
+        info!("Victory: {}", *data.world.read_resource::<Stats>());
+
+        let font = load_font(data.world);
+        let colors = data.world.read_resource::<Themes>().selected.colors.clone();
+        let victory = UiTransform::new(
+            "victory".to_string(),
+            Anchor::TopMiddle,
+            Anchor::TopMiddle,
+            0.0,
+            -50.,
+            1.,
+            1000.,
+            300.,
+        );
+        data.world
+            .create_entity()
+            .with(victory)
+            .with(UiText::new(
+                font,
+                "You Win!".to_string(),
+                colors.highlight,
+                150.,
+            ))
+            .build();
+
+        let mut victory_time = VictoryTime {
+            time: Stopwatch::new(),
+        };
+        victory_time.time.start();
+        data.world.insert(victory_time);
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let victory_time = data.world.read_resource::<VictoryTime>();
+        let mut trans = SimpleTrans::None;
+        if victory_time.time.elapsed().as_millis() > 4000 {
+            trans = SimpleTrans::Replace(Box::new(SnakeGame));
+        }
+        trans
+    }
+}
+
+struct VictoryTime {
+    time: Stopwatch,
+}